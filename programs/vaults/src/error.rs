@@ -17,6 +17,162 @@ pub enum ErrorCode {
 
     #[msg("The given token has outstanding LP token supply.")]
     TokenWithLpSupply,
+
+    #[msg("The given fee exceeds the maximum allowed.")]
+    InvalidFee,
+
+    #[msg("The withdraw ticket can only be settled by the manager until it expires.")]
+    WithdrawTicketNotExpired,
+
+    #[msg("The deposits of this position are still locked.")]
+    DepositLocked,
+
+    #[msg("Deposits of the given token are disabled.")]
+    DepositsDisabled,
+
+    #[msg("The deposit would exceed the token's deposit limit.")]
+    DepositLimitExceeded,
+
+    #[msg("The deposit would exceed the vault's deposit limit.")]
+    VaultDepositLimitExceeded,
+
+    #[msg("The given token mint is already supported.")]
+    TokenAlreadySupported,
+
+    #[msg("The given LP token mint does not belong to the given token mint.")]
+    InvalidLpMint,
+
+    #[msg("The given token is disabled.")]
+    TokenDisabled,
+
+    #[msg("The given Cypher account does not belong to the vault.")]
+    InvalidCypherAccount,
+
+    #[msg("The minimum margin ratio is below Cypher's maintenance margin.")]
+    InvalidMarginRatio,
+
+    #[msg("The vault holds a position in a market that is not allowed.")]
+    MarketNotAllowed,
+
+    #[msg("The vault's position in a market exceeds the maximum notional.")]
+    MarketNotionalExceeded,
+
+    #[msg("The vault's leverage exceeds the maximum leverage.")]
+    LeverageExceeded,
+
+    #[msg("The vault's margin ratio is below the minimum margin ratio.")]
+    MarginRatioBreached,
+
+    #[msg("A math operation overflowed.")]
+    MathOverflow,
+
+    #[msg("The amount of LP tokens is outside of the given slippage bounds.")]
+    SlippageExceeded,
+
+    #[msg("The signer is not the pending authority of the vault.")]
+    InvalidPendingAuthority,
+
+    #[msg("The signer is neither the guardian nor the authority of the vault.")]
+    InvalidGuardian,
+
+    #[msg("The guardian can not loosen the pause state of the vault.")]
+    InvalidPauseState,
+
+    #[msg("The vault is paused.")]
+    VaultPaused,
+
+    #[msg("The vault has not been migrated to the current version.")]
+    VaultNotMigrated,
+
+    #[msg("The vault has already been migrated to the current version.")]
+    VaultAlreadyMigrated,
+
+    #[msg("The account is not a vault of this program.")]
+    InvalidVaultAccount,
+
+    #[msg("The signer is not the authority of the vault.")]
+    InvalidAuthority,
+
+    #[msg("The vault can not accept any more tokens.")]
+    TooManyTokens,
+
+    #[msg("The epoch duration is invalid.")]
+    InvalidEpochDuration,
+
+    #[msg("The deposits and withdrawals of an epoch vault have to be queued.")]
+    EpochModeEnabled,
+
+    #[msg("The vault is not an epoch vault.")]
+    EpochModeDisabled,
+
+    #[msg("The current epoch has not ended yet.")]
+    EpochNotOver,
+
+    #[msg("The epoch ticket of a previous epoch has to be claimed first.")]
+    EpochTicketUnclaimed,
+
+    #[msg("The epoch of the epoch ticket has not been rolled yet.")]
+    EpochNotRolled,
+
+    #[msg("The config timelock is invalid.")]
+    InvalidConfigTimelock,
+
+    #[msg("The configuration changes of this vault have to be queued.")]
+    ConfigChangeTimelocked,
+
+    #[msg("The timelock of the configuration change has not passed yet.")]
+    ConfigChangeNotExecutable,
+
+    #[msg("The vault is winding down.")]
+    VaultWindingDown,
+
+    #[msg("The vault is not winding down.")]
+    VaultNotWindingDown,
+
+    #[msg("The vault still has positions open.")]
+    PositionsOpen,
+
+    #[msg("The accounts of the tokens being claimed are invalid.")]
+    InvalidClaimAccounts,
+
+    #[msg("The cypher sub account still has positions or balances.")]
+    SubAccountNotEmpty,
+
+    #[msg("The vault can not have any more strategy sub accounts.")]
+    TooManySubAccounts,
+
+    #[msg("The strategy sub accounts of the vault are missing or invalid.")]
+    InvalidStrategySubAccounts,
+
+    #[msg("The fees of every LP token must be accrued before the fees can be changed.")]
+    FeesNotAccrued,
+
+    #[msg("The maximum withdraw wait can not be negative.")]
+    InvalidMaxWithdrawWait,
+
+    #[msg("The lockup period can not be negative.")]
+    InvalidLockupPeriod,
+
+    #[msg("The cache index does not match the Cypher pool of the SPL Token.")]
+    InvalidCacheIndex,
+
+    #[msg("The amount of LP tokens or SPL Tokens is zero.")]
+    ZeroAmount,
+
+    #[msg("The epoch of the epoch ticket has already been rolled.")]
+    EpochTicketRolled,
+
+    #[msg("A vault that is winding down can only reduce its positions.")]
+    PositionIncreased,
+
+    #[msg("The amount of LP tokens exceeds their supply.")]
+    InvalidLpAmount,
+
+    #[msg("The owner of the depositor position has not approved deposits on their behalf.")]
+    DepositForNotApproved,
+
+    #[msg("The vault still has queued deposits, withdrawals or tickets waiting to be settled.")]
+    UnsettledFlows,
 }
 
 #[macro_export]
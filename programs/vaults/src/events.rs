@@ -0,0 +1,560 @@
+use anchor_lang::prelude::*;
+
+use crate::{ConfigChange, MarketType, PauseState, RiskParams, TradeOperation, VaultType};
+
+/// Emitted when a [`crate::Vault`] is created.
+#[event]
+pub struct VaultCreated {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The authority of the [`crate::Vault`].
+    pub authority: Pubkey,
+    /// The manager of the [`crate::Vault`].
+    pub manager: Pubkey,
+    /// The id of the [`crate::Vault`].
+    pub id: u64,
+    /// The type of the [`crate::Vault`].
+    pub vault_type: VaultType,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a [`crate::Vault`] is closed.
+#[event]
+pub struct VaultClosed {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the authority sweeps the dust left in a [`crate::Vault`] whose LP tokens have all been burned.
+#[event]
+pub struct DustSwept {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The [`cypher_client::CypherSubAccount`] the dust was swept from.
+    pub sub_account: Pubkey,
+    /// The SPL Token Mint swept.
+    pub token_mint: Pubkey,
+    /// The amount swept, in native units of the SPL Token.
+    pub amount: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a [`crate::Vault`] is migrated to the current layout.
+#[event]
+pub struct VaultMigrated {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The version of the layout the [`crate::Vault`] was migrated from.
+    pub from_version: u8,
+    /// The version of the layout the [`crate::Vault`] was migrated to.
+    pub to_version: u8,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the vault-wide LP token of a [`crate::Vault`] is created.
+#[event]
+pub struct VaultLpMintCreated {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The address of the vault-wide LP token Mint.
+    pub lp_mint: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a [`crate::Vault`] starts accepting deposits of an SPL Token.
+#[event]
+pub struct DepositsOpened {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint accepted for deposits.
+    pub token_mint: Pubkey,
+    /// The LP token Mint issued for deposits of the SPL Token.
+    pub lp_mint: Pubkey,
+    /// The deposit limit of the SPL Token, in its native units.
+    pub deposit_limit: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a [`crate::Vault`] stops accepting deposits of an SPL Token for good.
+#[event]
+pub struct DepositsClosed {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint no longer accepted for deposits.
+    pub token_mint: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when deposits of an SPL Token are enabled.
+#[event]
+pub struct DepositsEnabled {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint whose deposits are enabled.
+    pub token_mint: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when deposits of an SPL Token are disabled.
+#[event]
+pub struct DepositsDisabled {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint whose deposits are disabled.
+    pub token_mint: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the deposit limit of an SPL Token is set.
+#[event]
+pub struct DepositLimitSet {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint whose deposit limit is set.
+    pub token_mint: Pubkey,
+    /// The new deposit limit, in native units of the SPL Token.
+    pub deposit_limit: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the vault-wide deposit limit is set.
+#[event]
+pub struct VaultDepositLimitSet {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The new vault-wide deposit limit, in native units of the quote token.
+    pub quote_deposit_limit: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the lockup period of an SPL Token is set.
+#[event]
+pub struct LockupPeriodSet {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint whose lockup period is set.
+    pub token_mint: Pubkey,
+    /// The new lockup period, in seconds.
+    pub lockup_period: i64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the LP tokens in a depositor's lockup escrow are released.
+#[event]
+pub struct LockupReleased {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint the LP tokens were received for.
+    pub token_mint: Pubkey,
+    /// The owner of the released LP tokens.
+    pub owner: Pubkey,
+    /// The amount of LP tokens released.
+    pub lp_amount: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the owner of a [`crate::DepositorPosition`] approves or revokes deposits on their behalf.
+#[event]
+pub struct DepositsForApproved {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint of the [`crate::DepositorPosition`].
+    pub token_mint: Pubkey,
+    /// The owner of the [`crate::DepositorPosition`].
+    pub owner: Pubkey,
+    /// Whether deposits through `deposit_for` are approved.
+    pub approved: bool,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the maximum withdraw wait is set.
+#[event]
+pub struct MaxWithdrawWaitSet {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The new maximum withdraw wait, in seconds.
+    pub max_withdraw_wait: i64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the fees of a [`crate::Vault`] are set.
+#[event]
+pub struct FeesSet {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The owner of the LP token accounts the fees are minted to.
+    pub fee_recipient: Pubkey,
+    /// The new annual management fee, in basis points.
+    pub management_fee_bps: u16,
+    /// The new performance fee, in basis points.
+    pub performance_fee_bps: u16,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted every time the fees of an LP token are accrued, even if no fee was owed.
+#[event]
+pub struct FeesAccrued {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint whose LP token accrued the fees.
+    pub token_mint: Pubkey,
+    /// The owner of the LP token account the fees were minted to.
+    pub fee_recipient: Pubkey,
+    /// The amount of LP tokens minted as fees.
+    pub lp_minted: u64,
+    /// The share price, scaled by [`crate::SHARE_PRICE_SCALE`].
+    pub share_price: u128,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the manager of a [`crate::Vault`] is set.
+#[event]
+pub struct ManagerSet {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The new manager.
+    pub manager: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a new authority of a [`crate::Vault`] is proposed.
+#[event]
+pub struct AuthorityProposed {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The current authority.
+    pub authority: Pubkey,
+    /// The proposed authority, which has to accept the role.
+    pub pending_authority: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the proposed authority of a [`crate::Vault`] accepts the role.
+#[event]
+pub struct AuthorityTransferred {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The previous authority.
+    pub previous_authority: Pubkey,
+    /// The new authority.
+    pub authority: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the guardian of a [`crate::Vault`] is set.
+#[event]
+pub struct GuardianSet {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The new guardian.
+    pub guardian: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a [`crate::Vault`] is paused or unpaused.
+#[event]
+pub struct PauseStateSet {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The authority or guardian that changed the pause state.
+    pub signer: Pubkey,
+    /// The new pause state.
+    pub pause_state: PauseState,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the risk limits of one of a [`crate::Vault`]'s sub accounts are set.
+#[event]
+pub struct RiskParamsSet {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The number of the [`cypher_client::CypherSubAccount`].
+    pub sub_account_number: u8,
+    /// The new risk limits.
+    pub risk_params: RiskParams,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a strategy sub account is created.
+#[event]
+pub struct StrategySubAccountCreated {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The address of the new [`cypher_client::CypherSubAccount`].
+    pub sub_account: Pubkey,
+    /// The number of the new [`cypher_client::CypherSubAccount`].
+    pub sub_account_number: u8,
+    /// The risk limits of the new [`cypher_client::CypherSubAccount`].
+    pub risk_params: RiskParams,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when collateral is moved between the sub accounts of a [`crate::Vault`].
+#[event]
+pub struct CollateralTransferred {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The manager that moved the collateral.
+    pub manager: Pubkey,
+    /// The [`cypher_client::CypherSubAccount`] the collateral was moved from.
+    pub from_sub_account: Pubkey,
+    /// The [`cypher_client::CypherSubAccount`] the collateral was moved to.
+    pub to_sub_account: Pubkey,
+    /// The Cypher pool of the SPL Token moved.
+    pub pool: Pubkey,
+    /// The amount moved, in native units of the SPL Token.
+    pub amount: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when an SPL Token is deposited into a [`crate::Vault`].
+#[event]
+pub struct Deposited {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint deposited.
+    pub token_mint: Pubkey,
+    /// The owner of the [`crate::DepositorPosition`] the deposit was recorded in.
+    pub owner: Pubkey,
+    /// The amount deposited, in native units of the SPL Token.
+    pub amount: u64,
+    /// The amount of LP tokens minted for the deposit.
+    pub lp_minted: u64,
+    /// The share price, scaled by [`crate::SHARE_PRICE_SCALE`].
+    pub share_price: u128,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when an SPL Token is withdrawn from a [`crate::Vault`].
+#[event]
+pub struct Withdrawn {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint withdrawn.
+    pub token_mint: Pubkey,
+    /// The owner of the LP tokens burned.
+    pub owner: Pubkey,
+    /// The amount withdrawn, in native units of the SPL Token.
+    pub amount: u64,
+    /// The amount of LP tokens burned for the withdrawal.
+    pub lp_burned: u64,
+    /// The share price, scaled by [`crate::SHARE_PRICE_SCALE`].
+    pub share_price: u128,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a withdrawal is requested through a [`crate::WithdrawTicket`].
+#[event]
+pub struct WithdrawRequested {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint to be withdrawn.
+    pub token_mint: Pubkey,
+    /// The owner of the LP tokens locked in escrow.
+    pub owner: Pubkey,
+    /// The address of the [`crate::WithdrawTicket`].
+    pub withdraw_ticket: Pubkey,
+    /// The amount of LP tokens locked in escrow.
+    pub lp_amount: u64,
+    /// The minimum amount of the SPL Token the LP tokens have to be redeemed for.
+    pub min_tokens_out: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a [`ConfigChange`] is queued behind the timelock.
+#[event]
+pub struct ConfigChangeQueued {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The address of the [`crate::PendingConfigChange`].
+    pub pending_config_change: Pubkey,
+    /// The queued change.
+    pub change: ConfigChange,
+    /// The timestamp from which the change can be executed.
+    pub executable_at: i64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a queued [`ConfigChange`] is executed.
+#[event]
+pub struct ConfigChangeExecuted {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The address of the [`crate::PendingConfigChange`].
+    pub pending_config_change: Pubkey,
+    /// The executed change.
+    pub change: ConfigChange,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a queued [`ConfigChange`] is cancelled.
+#[event]
+pub struct ConfigChangeCancelled {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The address of the [`crate::PendingConfigChange`].
+    pub pending_config_change: Pubkey,
+    /// The cancelled change.
+    pub change: ConfigChange,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a [`crate::Vault`] starts winding down.
+#[event]
+pub struct WindDownStarted {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when an LP claims their share of a winding down [`crate::Vault`].
+#[event]
+pub struct WindDownClaimed {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The SPL Token Mint whose LP token was burned.
+    pub token_mint: Pubkey,
+    /// The owner of the LP tokens burned.
+    pub owner: Pubkey,
+    /// The amount of LP tokens burned.
+    pub lp_burned: u64,
+    /// The SPL Token Mints paid out, in the same order as `amounts`.
+    pub token_mints: Vec<Pubkey>,
+    /// The amounts paid out, in native units of the SPL Token at the same index of `token_mints`.
+    pub amounts: Vec<u64>,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a deposit is queued for the next epoch roll.
+#[event]
+pub struct DepositQueued {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The owner of the [`crate::EpochTicket`].
+    pub owner: Pubkey,
+    /// The epoch the deposit was queued in.
+    pub epoch: u64,
+    /// The amount queued, in native units of the SPL Token.
+    pub amount: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when a withdrawal is queued for the next epoch roll.
+#[event]
+pub struct WithdrawQueued {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The owner of the [`crate::EpochTicket`].
+    pub owner: Pubkey,
+    /// The epoch the withdrawal was queued in.
+    pub epoch: u64,
+    /// The amount of LP tokens queued.
+    pub lp_amount: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the deposit and withdrawal queued in an [`crate::EpochTicket`] are cancelled before the epoch roll.
+#[event]
+pub struct EpochTicketCancelled {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The owner of the [`crate::EpochTicket`].
+    pub owner: Pubkey,
+    /// The epoch the deposit and withdrawal were queued in.
+    pub epoch: u64,
+    /// The amount of the queued deposit returned, in native units of the SPL Token.
+    pub deposit_amount: u64,
+    /// The amount of queued LP tokens returned.
+    pub lp_amount: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when an epoch [`crate::Vault`] settles the queued deposits and withdrawals of an epoch.
+#[event]
+pub struct EpochRolled {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The epoch that was rolled.
+    pub epoch: u64,
+    /// The amount of queued deposits, in native units of the SPL Token.
+    pub deposits: u64,
+    /// The amount of LP tokens minted for the queued deposits.
+    pub lp_minted: u64,
+    /// The amount of LP tokens burned for the queued withdrawals.
+    pub lp_burned: u64,
+    /// The amount withdrawn for the queued withdrawals, in native units of the SPL Token.
+    pub withdrawn: u64,
+    /// The share price, scaled by [`crate::SHARE_PRICE_SCALE`].
+    pub share_price: u128,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the settled deposit and withdrawal of an [`crate::EpochTicket`] are claimed.
+#[event]
+pub struct EpochClaimed {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The owner of the [`crate::EpochTicket`].
+    pub owner: Pubkey,
+    /// The epoch the deposit and withdrawal were settled in.
+    pub epoch: u64,
+    /// The amount of LP tokens claimed for the deposit.
+    pub lp_amount: u64,
+    /// The amount claimed for the withdrawal, in native units of the SPL Token.
+    pub token_amount: u64,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
+
+/// Emitted when the manager trades through one of the [`crate::Vault`]'s sub accounts.
+#[event]
+pub struct TradeExecuted {
+    /// The address of the [`crate::Vault`].
+    pub vault: Pubkey,
+    /// The manager that traded.
+    pub manager: Pubkey,
+    /// The type of market traded on.
+    pub market_type: MarketType,
+    /// The trading operation.
+    pub operation: TradeOperation,
+    /// The timestamp of the event.
+    pub timestamp: i64,
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{check, error::ErrorCode, AuthorityTransferred, Vault};
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub pending_authority: Signer<'info>,
+}
+
+impl<'info> AcceptAuthority<'info> {
+    /// Validate that the signer is the authority proposed by the current authority.
+    pub fn validate(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        check!(
+            vault.pending_authority != Pubkey::default()
+                && vault.pending_authority == self.pending_authority.key(),
+            InvalidPendingAuthority
+        );
+        Ok(())
+    }
+
+    /// Transfers the authority of the [`Vault`] to the pending authority, returning the previous authority.
+    fn accept_authority(&mut self) -> Result<Pubkey> {
+        let mut vault = self.vault.load_mut()?;
+        let previous_authority = vault.authority;
+        vault.authority = vault.pending_authority;
+        vault.pending_authority = Pubkey::default();
+        Ok(previous_authority)
+    }
+}
+
+pub fn handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let previous_authority = ctx.accounts.accept_authority()?;
+
+    emit!(AuthorityTransferred {
+        vault: ctx.accounts.vault.key(),
+        previous_authority,
+        authority: ctx.accounts.pending_authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+use cypher_client::{CacheAccount, CypherSubAccount};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::FeesAccrued,
+    state::{Vault, VAULT_SEED},
+};
+
+/// The accounts used to accrue the fees of an LP token of a [`Vault`].
+///
+/// The [`Vault`]'s strategy sub accounts are passed as remaining accounts, see [`Vault::load_sub_accounts`].
+#[derive(Accounts)]
+pub struct AccrueFees<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = vault.load()?.fee_recipient,
+    )]
+    pub fee_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> AccrueFees<'info> {
+    /// We need to validate that we have the correct SPL Token and LP token.
+    pub fn validate(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let share_info = vault.get_share_info(self.token_mint.key()).unwrap();
+        check!(self.lp_mint.key() == share_info.lp_mint, InvalidLpMint);
+        Ok(())
+    }
+
+    /// Gets the marked to market value backing the LP token of the SPL Token.
+    pub fn get_share_net_assets(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let vault = self.vault.load()?;
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        vault.get_share_net_assets(&self.token_mint.key(), &sub_accounts, &cache_account)
+    }
+
+    /// Mint the given amount of LP tokens to the fee recipient.
+    pub fn invoke_mint_to(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = MintTo {
+            mint: self.lp_mint.to_account_info(),
+            to: self.fee_token_account.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        mint_to(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+}
+
+/// Anyone can accrue the fees owed to the fee recipient up until now.
+///
+/// This has to be done for every LP token before the fees can be changed, so the new rates do not apply retroactively.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, AccrueFees<'info>>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let net_assets = ctx.accounts.get_share_net_assets(ctx.remaining_accounts)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let token_mint = ctx.accounts.token_mint.key();
+    let fee_amount = ctx
+        .accounts
+        .vault
+        .load_mut()?
+        .accrue_fees(token_mint, net_assets, now)?;
+    if fee_amount != 0 {
+        ctx.accounts.invoke_mint_to(fee_amount)?;
+    }
+    let share_price = ctx
+        .accounts
+        .vault
+        .load()?
+        .get_share_price(token_mint, net_assets)?;
+
+    emit!(FeesAccrued {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        fee_recipient: ctx.accounts.vault.load()?.fee_recipient,
+        lp_minted: fee_amount,
+        share_price,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{
+    error::ErrorCode,
+    events::DepositsForApproved,
+    state::{DepositorPosition, Vault, DEPOSITOR_POSITION_SEED},
+};
+
+#[derive(Accounts)]
+pub struct ApproveDepositsFor<'info> {
+    #[account(
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            DEPOSITOR_POSITION_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = DepositorPosition::LEN,
+    )]
+    pub depositor_position: Box<Account<'info, DepositorPosition>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// The owner approves or revokes deposits into their [`DepositorPosition`] through `deposit_for`
+/// while the SPL Token has a lockup period, as these restart the lockup period of the position.
+pub fn handler(ctx: Context<ApproveDepositsFor>, approved: bool) -> Result<()> {
+    let position_bump = *ctx.bumps.get("depositor_position").unwrap();
+    let vault = ctx.accounts.vault.key();
+    let token_mint = ctx.accounts.token_mint.key();
+    let owner = ctx.accounts.owner.key();
+
+    let depositor_position = &mut ctx.accounts.depositor_position;
+    if depositor_position.owner == Pubkey::default() {
+        depositor_position.init(vault, token_mint, owner, position_bump);
+    }
+    depositor_position.approves_deposits_for = approved;
+
+    emit!(DepositsForApproved {
+        vault,
+        token_mint,
+        owner,
+        approved,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, ConfigChangeCancelled, PendingConfigChange, Vault};
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        close = authority,
+    )]
+    pub pending_config_change: Box<Account<'info, PendingConfigChange>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// The authority cancels a queued [`ConfigChange`] before it is executed.
+pub fn handler(ctx: Context<CancelConfigChange>) -> Result<()> {
+    emit!(ConfigChangeCancelled {
+        vault: ctx.accounts.vault.key(),
+        pending_config_change: ctx.accounts.pending_config_change.key(),
+        change: ctx.accounts.pending_config_change.change,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
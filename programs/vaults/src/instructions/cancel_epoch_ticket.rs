@@ -0,0 +1,170 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::EpochTicketCancelled,
+    state::{
+        EpochTicket, Vault, EPOCH_LP_ESCROW_SEED, EPOCH_TICKET_SEED, EPOCH_TOKEN_ESCROW_SEED,
+        VAULT_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct CancelEpochTicket<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            EPOCH_TICKET_SEED,
+            vault.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump = epoch_ticket.bump,
+        close = authority,
+    )]
+    pub epoch_ticket: Box<Account<'info, EpochTicket>>,
+
+    #[account(
+        mut,
+        seeds = [
+            EPOCH_TOKEN_ESCROW_SEED,
+            vault.key().as_ref(),
+        ],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault,
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            EPOCH_LP_ESCROW_SEED,
+            vault.key().as_ref(),
+        ],
+        bump,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub escrow_lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = authority,
+    )]
+    pub lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = authority,
+    )]
+    pub destination_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CancelEpochTicket<'info> {
+    /// We need to validate that we have the correct SPL Token and that the epoch of the [`EpochTicket`] was not rolled yet.
+    pub fn validate(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        check!(self.lp_mint.key() == token_info.lp_mint, InvalidLpMint);
+        check!(self.epoch_ticket.epoch == vault.epoch, EpochTicketRolled);
+        Ok(())
+    }
+
+    /// Transfer the given amount out of one of the [`Vault`]'s epoch escrows.
+    pub fn invoke_transfer(
+        &self,
+        from: AccountInfo<'info>,
+        to: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from,
+            to,
+            authority: self.vault.to_account_info(),
+        };
+        transfer(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+}
+
+/// The user wants to take back the deposit and withdrawal they queued in the current epoch before it is rolled.
+///
+/// This is allowed even while the [`Vault`] is paused, since only the user's own escrowed tokens move.
+pub fn handler(ctx: Context<CancelEpochTicket>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let epoch_ticket = &ctx.accounts.epoch_ticket;
+    let deposit_amount = epoch_ticket.deposit_amount;
+    let lp_amount = epoch_ticket.lp_amount;
+    let epoch = epoch_ticket.epoch;
+
+    if deposit_amount != 0 {
+        let from = ctx.accounts.escrow_token_account.to_account_info();
+        let to = ctx.accounts.destination_token_account.to_account_info();
+        ctx.accounts.invoke_transfer(from, to, deposit_amount)?;
+    }
+    if lp_amount != 0 {
+        let from = ctx.accounts.escrow_lp_token_account.to_account_info();
+        let to = ctx.accounts.lp_token_account.to_account_info();
+        ctx.accounts.invoke_transfer(from, to, lp_amount)?;
+    }
+
+    {
+        let mut vault = ctx.accounts.vault.load_mut()?;
+        vault.pending_deposits = vault
+            .pending_deposits
+            .checked_sub(deposit_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        vault.pending_withdrawals = vault
+            .pending_withdrawals
+            .checked_sub(lp_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        // tickets opened before the count was kept are not part of it
+        vault.epoch_ticket_count = vault.epoch_ticket_count.saturating_sub(1);
+    }
+
+    emit!(EpochTicketCancelled {
+        vault: ctx.accounts.vault.key(),
+        owner: ctx.accounts.authority.key(),
+        epoch,
+        deposit_amount,
+        lp_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use cypher_client::CancelOrderArgs;
+
+use crate::{MarketType, Trade, TradeOperation};
+
+/// Cancels an order on a Cypher market, signed by the [`crate::Vault`].
+///
+/// The market's accounts are passed as remaining accounts, see [`crate::DerivativeMarketAccounts`] and [`crate::SpotMarketAccounts`].
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+    market_type: MarketType,
+    args: CancelOrderArgs,
+) -> Result<()> {
+    ctx.accounts.validate(TradeOperation::CancelOrder)?;
+
+    ctx.accounts
+        .invoke_cancel_order(ctx.remaining_accounts, market_type, args)?;
+    ctx.accounts
+        .emit_trade_executed(market_type, TradeOperation::CancelOrder)?;
+
+    // this can only reduce the [`crate::Vault`]'s risk, so it is not held to the risk limits
+    Ok(())
+}
@@ -0,0 +1,245 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::EpochClaimed,
+    state::{
+        DepositorPosition, EpochRecord, EpochTicket, Vault, DEPOSITOR_POSITION_SEED,
+        EPOCH_LP_ESCROW_SEED, EPOCH_RECORD_SEED, EPOCH_TICKET_SEED, EPOCH_TOKEN_ESCROW_SEED,
+        LOCKUP_ESCROW_SEED, VAULT_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ClaimEpoch<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            EPOCH_TICKET_SEED,
+            vault.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump = epoch_ticket.bump,
+        close = authority,
+    )]
+    pub epoch_ticket: Box<Account<'info, EpochTicket>>,
+
+    #[account(
+        seeds = [
+            EPOCH_RECORD_SEED,
+            vault.key().as_ref(),
+            epoch_ticket.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub epoch_record: Box<Account<'info, EpochRecord>>,
+
+    #[account(
+        mut,
+        seeds = [
+            EPOCH_TOKEN_ESCROW_SEED,
+            vault.key().as_ref(),
+        ],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault,
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            EPOCH_LP_ESCROW_SEED,
+            vault.key().as_ref(),
+        ],
+        bump,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub escrow_lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            DEPOSITOR_POSITION_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = DepositorPosition::LEN,
+    )]
+    pub depositor_position: Box<Account<'info, DepositorPosition>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            LOCKUP_ESCROW_SEED,
+            depositor_position.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub lockup_escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = authority,
+    )]
+    pub lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = authority,
+    )]
+    pub destination_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> ClaimEpoch<'info> {
+    /// We need to validate that we have the correct SPL Token and that the epoch of the [`EpochTicket`] was rolled.
+    pub fn validate(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        check!(self.lp_mint.key() == token_info.lp_mint, InvalidLpMint);
+        check!(vault.pause_state().allows_withdrawals(), VaultPaused);
+        check!(self.epoch_ticket.epoch < vault.epoch, EpochNotRolled);
+        Ok(())
+    }
+
+    /// Gets the lockup period of the SPL Token the LP tokens were issued for.
+    pub fn lockup_period(&self) -> Result<i64> {
+        let vault = self.vault.load()?;
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        Ok(token_info.lockup_period)
+    }
+
+    /// Transfer the given amount out of one of the [`Vault`]'s epoch escrows.
+    pub fn invoke_transfer(
+        &self,
+        from: AccountInfo<'info>,
+        to: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from,
+            to,
+            authority: self.vault.to_account_info(),
+        };
+        transfer(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+}
+
+/// The user wants to claim the LP tokens and SPL Tokens owed for what they queued in an epoch that has been rolled,
+/// their pro-rata share of the amounts in the epoch's [`EpochRecord`].
+///
+/// While the SPL Token has a lockup period the LP tokens are held in the depositor's lockup escrow,
+/// which restarts the lockup period of everything in it, until they are released by `release_lockup`.
+pub fn handler(ctx: Context<ClaimEpoch>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let epoch_record = &ctx.accounts.epoch_record;
+    let epoch_ticket = &ctx.accounts.epoch_ticket;
+    let deposit_amount = epoch_ticket.deposit_amount;
+    let burn_amount = epoch_ticket.lp_amount;
+    let lp_amount = epoch_record.calculate_claim_lp_amount(deposit_amount)?;
+    let token_amount = epoch_record.calculate_claim_token_amount(burn_amount)?;
+    let epoch = epoch_ticket.epoch;
+
+    // the LP tokens are held in the depositor's lockup escrow while the SPL Token has a lockup period, as with `deposit`
+    if lp_amount != 0 {
+        let from = ctx.accounts.escrow_lp_token_account.to_account_info();
+        let to = if ctx.accounts.lockup_period()? > 0 {
+            ctx.accounts.lockup_escrow_token_account.to_account_info()
+        } else {
+            ctx.accounts.lp_token_account.to_account_info()
+        };
+        ctx.accounts.invoke_transfer(from, to, lp_amount)?;
+    }
+    if token_amount != 0 {
+        let from = ctx.accounts.escrow_token_account.to_account_info();
+        let to = ctx.accounts.destination_token_account.to_account_info();
+        ctx.accounts.invoke_transfer(from, to, token_amount)?;
+    }
+
+    // the [`EpochTicket`] is closed once claimed, tickets opened before the count was kept are not part of it
+    {
+        let mut vault = ctx.accounts.vault.load_mut()?;
+        vault.epoch_ticket_count = vault.epoch_ticket_count.saturating_sub(1);
+    }
+
+    // record the processed flows in the depositor's position, a single token vault prices its LP token in that token
+    let now = Clock::get()?.unix_timestamp;
+    let position_bump = *ctx.bumps.get("depositor_position").unwrap();
+    let vault = ctx.accounts.vault.key();
+    let owner = ctx.accounts.authority.key();
+    let token_mint = ctx.accounts.token_mint.key();
+    let depositor_position = &mut ctx.accounts.depositor_position;
+    if depositor_position.owner == Pubkey::default() {
+        depositor_position.init(vault, token_mint, owner, position_bump);
+    }
+    if deposit_amount != 0 {
+        depositor_position.record_deposit(deposit_amount, deposit_amount, lp_amount);
+        depositor_position.restart_lockup(now);
+    }
+    if burn_amount != 0 {
+        depositor_position.record_withdraw(token_amount, burn_amount);
+    }
+
+    emit!(EpochClaimed {
+        vault,
+        owner,
+        epoch,
+        lp_amount,
+        token_amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+use cypher_client::{
+    cpi::{accounts::WithdrawFunds, withdraw_funds},
+    program::Cypher,
+    utils::{derive_account_address, derive_sub_account_address},
+    CacheAccount, Clearing, CypherAccount, CypherSubAccount,
+};
+
+use crate::{check, error::ErrorCode, Vault, WindDownClaimed, VAULT_SEED};
+
+/// The number of remaining accounts passed for every SPL Token being claimed.
+const CLAIM_ACCOUNTS_LEN: usize = 5;
+
+/// The accounts used by an LP to claim their share of a [`Vault`] that is winding down.
+///
+/// The [`Vault`]'s strategy sub accounts are passed first as remaining accounts, see [`Vault::load_sub_accounts`].
+/// Then for every SPL Token owed, in the order given by [`Vault::get_wind_down_claim_amounts`], the token mint,
+/// pool, pool node, token vault and the LP's destination token account are passed.
+#[derive(Accounts)]
+pub struct ClaimWindDown<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = authority,
+    )]
+    pub lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    #[account(mut)]
+    pub cypher_account: AccountLoader<'info, CypherAccount>,
+
+    #[account(mut)]
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    /// The SPL Token Mint the LP token was issued for.
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Checked via CPI to [`Cypher`].
+    pub vault_signer: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub cypher_program: Program<'info, Cypher>,
+}
+
+impl<'info> ClaimWindDown<'info> {
+    /// We need to validate that the [`Vault`] is winding down with all of its positions closed,
+    /// and with all of its collateral moved back into the primary [`CypherSubAccount`].
+    pub fn validate(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let vault = self.vault.load()?;
+        // the LPs of a winding down vault must always be able to claim their share, even while it is paused
+        check!(vault.is_winding_down(), VaultNotWindingDown);
+        let share_info = vault
+            .get_share_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        check!(self.lp_mint.key() == share_info.lp_mint, InvalidLpMint);
+
+        let (cypher_account, _) = derive_account_address(&self.vault.key(), vault.account_number);
+        check!(
+            self.cypher_account.key() == cypher_account,
+            InvalidCypherAccount
+        );
+        let (cypher_sub_account, _) =
+            derive_sub_account_address(&cypher_account, vault.sub_account_number);
+        check!(
+            self.cypher_sub_account.key() == cypher_sub_account,
+            InvalidCypherAccount
+        );
+
+        let sub_account = self.cypher_sub_account.load()?;
+        let cache_account = self.cache_account.load()?;
+        check!(
+            !Vault::has_open_positions(&sub_account, &cache_account),
+            PositionsOpen
+        );
+
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        for strategy_sub_account in sub_account_loaders.iter().skip(1) {
+            check!(
+                Vault::is_sub_account_empty(&strategy_sub_account.load()?, &cache_account),
+                SubAccountNotEmpty
+            );
+        }
+        Ok(())
+    }
+
+    /// Gets the amounts of each remaining SPL Token owed for the LP tokens being claimed.
+    pub fn get_claim_amounts(&self, lp_amount: u64) -> Result<Vec<(Pubkey, u64)>> {
+        let vault = self.vault.load()?;
+        let sub_account = self.cypher_sub_account.load()?;
+        let cache_account = self.cache_account.load()?;
+        vault.get_wind_down_claim_amounts(
+            self.token_mint.key(),
+            lp_amount,
+            &sub_account,
+            &cache_account,
+        )
+    }
+
+    /// Burn the LP tokens being claimed.
+    pub fn invoke_burn(&self, amount: u64) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Burn {
+            mint: self.lp_mint.to_account_info(),
+            from: self.lp_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        burn(CpiContext::new(cpi_program, cpi_accounts), amount)
+    }
+
+    /// Withdraw the input amount of an SPL Token from the [`cypher_client::CypherAccount`] to the LP.
+    pub fn invoke_withdraw_funds(
+        &self,
+        accounts: &[AccountInfo<'info>],
+        amount: u64,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.cypher_program.to_account_info();
+        let cpi_accounts = WithdrawFunds {
+            clearing: self.clearing.to_account_info(),
+            cache_account: self.cache_account.to_account_info(),
+            master_account: self.cypher_account.to_account_info(),
+            sub_account: self.cypher_sub_account.to_account_info(),
+            pool: accounts[1].clone(),
+            pool_node: accounts[2].clone(),
+            token_vault: accounts[3].clone(),
+            destination_token_account: accounts[4].clone(),
+            token_mint: accounts[0].clone(),
+            vault_signer: self.vault_signer.to_account_info(),
+            authority: self.vault.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        withdraw_funds(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+}
+
+/// The user wants to redeem `lp_amount` LP tokens of a [`Vault`] that is winding down,
+/// receiving their pro-rata share of every remaining SPL Token backing them.
+///
+/// This does not depend on the authority or the manager, so LPs can always exit once the positions are closed.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimWindDown<'info>>,
+    lp_amount: u64,
+) -> Result<()> {
+    ctx.accounts.validate(ctx.remaining_accounts)?;
+
+    // the amounts are calculated before anything leaves the [`CypherSubAccount`]
+    let claim_amounts = ctx.accounts.get_claim_amounts(lp_amount)?;
+    let strategy_sub_account_count = ctx.accounts.vault.load()?.strategy_sub_account_count as usize;
+    let claim_accounts = &ctx.remaining_accounts[strategy_sub_account_count..];
+    check!(
+        claim_accounts.len() == claim_amounts.len() * CLAIM_ACCOUNTS_LEN,
+        InvalidClaimAccounts
+    );
+
+    ctx.accounts.invoke_burn(lp_amount)?;
+
+    let owner = ctx.accounts.authority.key();
+    for ((token_mint, amount), accounts) in claim_amounts
+        .iter()
+        .zip(claim_accounts.chunks(CLAIM_ACCOUNTS_LEN))
+    {
+        check!(accounts[0].key() == *token_mint, InvalidClaimAccounts);
+        let destination_token_account = Account::<TokenAccount>::try_from(&accounts[4])?;
+        check!(
+            destination_token_account.mint == *token_mint
+                && destination_token_account.owner == owner,
+            InvalidClaimAccounts
+        );
+        ctx.accounts.invoke_withdraw_funds(accounts, *amount)?;
+    }
+
+    let token_mint = ctx.accounts.token_mint.key();
+    ctx.accounts
+        .vault
+        .load_mut()?
+        .record_wind_down_claim(token_mint, lp_amount, &claim_amounts)?;
+
+    let (token_mints, amounts) = claim_amounts.into_iter().unzip();
+    emit!(WindDownClaimed {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        owner,
+        lp_burned: lp_amount,
+        token_mints,
+        amounts,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
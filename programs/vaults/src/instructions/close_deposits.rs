@@ -1,15 +1,16 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
 
-use crate::{check, state::Vault};
+use crate::{check, error::ErrorCode, events::DepositsClosed, state::Vault};
 
 #[derive(Accounts)]
 pub struct CloseDeposits<'info> {
     #[account(
         mut,
-        has_one = authority,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
     )]
-    pub vault: Box<Account<'info, Vault>>,
+    pub vault: AccountLoader<'info, Vault>,
 
     #[account(
         mut,
@@ -29,7 +30,11 @@ pub struct CloseDeposits<'info> {
 impl<'info> CloseDeposits<'info> {
     /// Validate that this [`Vault`] does not have outstanding deposits and LP tokens.
     pub fn validate(&self, token_mint: Pubkey) -> Result<()> {
-        let token_info = self.vault.get_token_info(token_mint).unwrap();
+        let vault = self.vault.load()?;
+        let token_info = vault
+            .get_token_info(token_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        check!(self.lp_mint.key() == token_info.lp_mint, InvalidLpMint);
 
         // check that the deposits on the vault are zeroed
         check!(token_info.deposits == 0, TokenWithDeposits);
@@ -41,5 +46,13 @@ impl<'info> CloseDeposits<'info> {
 }
 
 pub fn handler(ctx: Context<CloseDeposits>, token_mint: Pubkey) -> Result<()> {
-    ctx.accounts.validate(token_mint)
+    ctx.accounts.validate(token_mint)?;
+
+    emit!(DepositsClosed {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
 }
@@ -1,15 +1,39 @@
 use anchor_lang::prelude::*;
+use cypher_client::{
+    cpi::{
+        accounts::{CloseAccount, CloseSubAccount},
+        close_account, close_sub_account,
+    },
+    program::Cypher,
+    utils::{derive_account_address, derive_sub_account_address},
+    CacheAccount, CypherAccount, CypherSubAccount,
+};
 
-use crate::{check, state::Vault};
+use crate::{
+    check,
+    error::ErrorCode,
+    events::VaultClosed,
+    state::{Vault, VAULT_SEED},
+};
 
+/// The [`Vault`]'s strategy sub accounts are passed as writable remaining accounts, see [`Vault::load_sub_accounts`].
 #[derive(Accounts)]
 pub struct CloseVault<'info> {
     #[account(
         mut,
-        has_one = authority,
-        close = rent_destination
+        close = rent_destination,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
     )]
-    pub vault: Box<Account<'info, Vault>>,
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    #[account(mut)]
+    pub cypher_account: AccountLoader<'info, CypherAccount>,
+
+    #[account(mut)]
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
 
     pub authority: Signer<'info>,
 
@@ -17,21 +41,112 @@ pub struct CloseVault<'info> {
     /// Vault authority needs to be careful when closing [`Vault`].
     #[account(mut)]
     pub rent_destination: AccountInfo<'info>,
+
+    pub cypher_program: Program<'info, Cypher>,
 }
 
 impl<'info> CloseVault<'info> {
-    /// Validate that this [`Vault`] does not have outstanding deposits and LP tokens.
-    pub fn validate(&self) -> Result<()> {
-        for token_info in self.vault.token_infos.iter() {
-            // check that the deposits for this SPL Token are zeroed
-            check!(token_info.deposits == 0, TokenWithDeposits);
-            // and the the supply of the corresponding LP token is also zero
-            check!(token_info.token_supply == 0, TokenWithLpSupply);
+    /// Validate that nothing is owed to any depositor of this [`Vault`] anymore and that its sub accounts are empty.
+    ///
+    /// Every LP token has to be burned, including the vault-wide LP token, and every queued flow and ticket settled.
+    /// The recorded deposits are not checked, rounding leaves them slightly off once every LP token is burned,
+    /// whatever dust is left in the sub accounts has to be swept with `sweep_dust` for them to be empty.
+    pub fn validate(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let vault = self.vault.load()?;
+        check!(!vault.has_lp_supply(), TokenWithLpSupply);
+        check!(!vault.has_unsettled_flows(), UnsettledFlows);
+
+        let (cypher_account, _) = derive_account_address(&self.vault.key(), vault.account_number);
+        check!(
+            self.cypher_account.key() == cypher_account,
+            InvalidCypherAccount
+        );
+        let (cypher_sub_account, _) =
+            derive_sub_account_address(&cypher_account, vault.sub_account_number);
+        check!(
+            self.cypher_sub_account.key() == cypher_sub_account,
+            InvalidCypherAccount
+        );
+
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let cache_account = self.cache_account.load()?;
+        for sub_account_loader in &sub_account_loaders {
+            check!(
+                Vault::is_sub_account_empty(&*sub_account_loader.load()?, &cache_account),
+                SubAccountNotEmpty
+            );
         }
         Ok(())
     }
+
+    /// Invokes [`Cypher`]'s [`CloseSubAccount`] instruction.
+    pub fn invoke_close_sub_account(&self, sub_account: AccountInfo<'info>) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.cypher_program.to_account_info();
+        let cpi_accounts = CloseSubAccount {
+            master_account: self.cypher_account.to_account_info(),
+            sub_account,
+            authority: self.vault.to_account_info(),
+            rent_destination: self.rent_destination.to_account_info(),
+        };
+        close_sub_account(CpiContext::new_with_signer(
+            cpi_program,
+            cpi_accounts,
+            &[&[
+                VAULT_SEED,
+                vault.creator.as_ref(),
+                vault.id.to_le_bytes().as_ref(),
+                &[vault.bump],
+            ]],
+        ))
+    }
+
+    /// Invokes [`Cypher`]'s [`CloseAccount`] instruction.
+    pub fn invoke_close_account(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.cypher_program.to_account_info();
+        let cpi_accounts = CloseAccount {
+            master_account: self.cypher_account.to_account_info(),
+            authority: self.vault.to_account_info(),
+            rent_destination: self.rent_destination.to_account_info(),
+        };
+        close_account(CpiContext::new_with_signer(
+            cpi_program,
+            cpi_accounts,
+            &[&[
+                VAULT_SEED,
+                vault.creator.as_ref(),
+                vault.id.to_le_bytes().as_ref(),
+                &[vault.bump],
+            ]],
+        ))
+    }
 }
 
-pub fn handler(ctx: Context<CloseVault>) -> Result<()> {
-    ctx.accounts.validate()
+/// The authority closes the [`Vault`] along with the Cypher accounts created for it,
+/// all of their rent is returned to `rent_destination`.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, CloseVault<'info>>) -> Result<()> {
+    ctx.accounts.validate(ctx.remaining_accounts)?;
+
+    // the sub accounts have to be closed before the master account they belong to
+    let strategy_sub_account_count = ctx.accounts.vault.load()?.strategy_sub_account_count as usize;
+    let cypher_sub_account = ctx.accounts.cypher_sub_account.to_account_info();
+    ctx.accounts.invoke_close_sub_account(cypher_sub_account)?;
+    for strategy_sub_account in &ctx.remaining_accounts[..strategy_sub_account_count] {
+        ctx.accounts
+            .invoke_close_sub_account(strategy_sub_account.clone())?;
+    }
+
+    ctx.accounts.invoke_close_account()?;
+
+    emit!(VaultClosed {
+        vault: ctx.accounts.vault.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
 }
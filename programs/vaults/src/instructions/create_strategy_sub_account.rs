@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use cypher_client::{
+    cpi::{accounts::CreateSubAccount, create_sub_account},
+    program::Cypher,
+    utils::{derive_account_address, derive_sub_account_address},
+    Clearing, CypherAccount, CypherSubAccount,
+};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::StrategySubAccountCreated,
+    state::{CreateStrategySubAccountArgs, StrategySubAccount, Vault, VAULT_SEED},
+};
+
+#[derive(Accounts)]
+pub struct CreateStrategySubAccount<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    #[account(mut)]
+    pub cypher_account: AccountLoader<'info, CypherAccount>,
+
+    #[account(mut)]
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub cypher_program: Program<'info, Cypher>,
+}
+
+impl<'info> CreateStrategySubAccount<'info> {
+    /// We need to validate that the [`Vault`] has room for another sub account and that its risk limits are valid.
+    pub fn validate(&self, args: &CreateStrategySubAccountArgs) -> Result<()> {
+        let vault = self.vault.load()?;
+        check!(!vault.is_winding_down(), VaultWindingDown);
+        check!(
+            !vault
+                .sub_account_numbers()
+                .contains(&args.sub_account_number),
+            InvalidCypherAccount
+        );
+
+        let (cypher_account, _) = derive_account_address(&self.vault.key(), vault.account_number);
+        check!(
+            self.cypher_account.key() == cypher_account,
+            InvalidCypherAccount
+        );
+        let (cypher_sub_account, _) =
+            derive_sub_account_address(&cypher_account, args.sub_account_number);
+        check!(
+            self.cypher_sub_account.key() == cypher_sub_account,
+            InvalidCypherAccount
+        );
+
+        args.risk_params.validate(&self.clearing.load()?)
+    }
+
+    /// Invokes [`Cypher`]'s [`CreateSubAccount`] instruction.
+    pub fn invoke_create_sub_account(&self, args: &CreateStrategySubAccountArgs) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.cypher_program.to_account_info();
+        let cpi_accounts = CreateSubAccount {
+            master_account: self.cypher_account.to_account_info(),
+            sub_account: self.cypher_sub_account.to_account_info(),
+            authority: self.vault.to_account_info(),
+            payer: self.payer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+        create_sub_account(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            args.sub_account_number,
+            args.sub_account_bump,
+            args.sub_account_alias,
+        )
+    }
+}
+
+/// The authority adds a [`CypherSubAccount`] to the [`Vault`] so a strategy can run with its own margin and risk limits.
+///
+/// The manager funds it by moving collateral from the [`Vault`]'s other sub accounts with `transfer_collateral`.
+///
+/// Its risk limits apply right away, so this is only allowed while the [`Vault`] has no configuration timelock.
+pub fn handler(
+    ctx: Context<CreateStrategySubAccount>,
+    args: CreateStrategySubAccountArgs,
+) -> Result<()> {
+    ctx.accounts.validate(&args)?;
+
+    ctx.accounts.invoke_create_sub_account(&args)?;
+
+    ctx.accounts
+        .vault
+        .load_mut()?
+        .add_strategy_sub_account(StrategySubAccount::new(&args))?;
+
+    emit!(StrategySubAccountCreated {
+        vault: ctx.accounts.vault.key(),
+        sub_account: ctx.accounts.cypher_sub_account.key(),
+        sub_account_number: args.sub_account_number,
+        risk_params: args.risk_params,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -8,7 +8,11 @@ use cypher_client::{
     Clearing, CypherAccount, CypherSubAccount,
 };
 
-use crate::state::{CreateVaultArgs, Vault, VAULT_SEED};
+use crate::{
+    check,
+    events::VaultCreated,
+    state::{CreateVaultArgs, Vault, VaultType, BPS_DENOMINATOR, VAULT_SEED},
+};
 
 #[derive(Accounts)]
 #[instruction(args: CreateVaultArgs)]
@@ -22,9 +26,9 @@ pub struct CreateVault<'info> {
         ],
         bump,
         payer = payer,
-        space = Vault::compute_vault_size(args.token_info_count)
+        space = Vault::LEN
     )]
-    pub vault: Box<Account<'info, Vault>>,
+    pub vault: AccountLoader<'info, Vault>,
 
     pub clearing: AccountLoader<'info, Clearing>,
 
@@ -47,8 +51,29 @@ pub struct CreateVault<'info> {
 }
 
 impl<'info> CreateVault<'info> {
+    /// We need to validate that the fees do not exceed 100% and that only a single token vault runs in epochs.
+    pub fn validate(&self, args: &CreateVaultArgs) -> Result<()> {
+        check!(
+            args.management_fee_bps as u128 <= BPS_DENOMINATOR,
+            InvalidFee
+        );
+        check!(
+            args.performance_fee_bps as u128 <= BPS_DENOMINATOR,
+            InvalidFee
+        );
+        check!(args.epoch_duration >= 0, InvalidEpochDuration);
+        check!(args.config_timelock >= 0, InvalidConfigTimelock);
+        check!(args.max_withdraw_wait >= 0, InvalidMaxWithdrawWait);
+        // an epoch is rolled at the share price of a single SPL Token
+        check!(
+            args.epoch_duration == 0 || args.vault_type == VaultType::SingleToken,
+            InvalidVaultType
+        );
+        Ok(())
+    }
+
     /// Invokes [`Cypher`]'s [`CreateAccount`] instruction.
-    pub fn invoke_create_account(&self, args: CreateVaultArgs) -> Result<()> {
+    pub fn invoke_create_account(&self, args: CreateVaultArgs, vault_bump: u8) -> Result<()> {
         let cpi_program = self.cypher_program.to_account_info();
         let cpi_accounts = CreateAccount {
             clearing: self.clearing.to_account_info(),
@@ -63,9 +88,9 @@ impl<'info> CreateVault<'info> {
                 cpi_accounts,
                 &[&[
                     VAULT_SEED,
-                    self.vault.authority.as_ref(),
-                    self.vault.id.to_le_bytes().as_ref(),
-                    &[self.vault.bump],
+                    self.authority.key().as_ref(),
+                    args.id.to_le_bytes().as_ref(),
+                    &[vault_bump],
                 ]],
             ),
             args.account_number,
@@ -74,7 +99,7 @@ impl<'info> CreateVault<'info> {
     }
 
     /// Invokes [`Cypher`]'s [`CreateSubAccount`] instruction.
-    pub fn invoke_create_sub_account(&self, args: CreateVaultArgs) -> Result<()> {
+    pub fn invoke_create_sub_account(&self, args: CreateVaultArgs, vault_bump: u8) -> Result<()> {
         let cpi_program = self.cypher_program.to_account_info();
         let cpi_accounts = CreateSubAccount {
             master_account: self.cypher_account.to_account_info(),
@@ -89,9 +114,9 @@ impl<'info> CreateVault<'info> {
                 cpi_accounts,
                 &[&[
                     VAULT_SEED,
-                    self.vault.authority.as_ref(),
-                    self.vault.id.to_le_bytes().as_ref(),
-                    &[self.vault.bump],
+                    self.authority.key().as_ref(),
+                    args.id.to_le_bytes().as_ref(),
+                    &[vault_bump],
                 ]],
             ),
             args.sub_account_number,
@@ -102,15 +127,29 @@ impl<'info> CreateVault<'info> {
 }
 
 pub fn handler(ctx: Context<CreateVault>, args: CreateVaultArgs) -> Result<()> {
-    let vault_bump = ctx.bumps.get("vault").unwrap();
+    ctx.accounts.validate(&args)?;
+
+    let vault_bump = *ctx.bumps.get("vault").unwrap();
+    let now = Clock::get()?.unix_timestamp;
 
-    let vault = &mut ctx.accounts.vault;
+    ctx.accounts
+        .vault
+        .load_init()?
+        .init(ctx.accounts.authority.key(), vault_bump, &args, now);
 
-    vault.init(ctx.accounts.authority.key(), *vault_bump, &args);
+    // the [`Vault`] can not be loaded again until its discriminator is written, so sign with the seeds it was created with
+    ctx.accounts.invoke_create_account(args, vault_bump)?;
 
-    ctx.accounts.invoke_create_account(args)?;
+    ctx.accounts.invoke_create_sub_account(args, vault_bump)?;
 
-    ctx.accounts.invoke_create_sub_account(args)?;
+    emit!(VaultCreated {
+        vault: ctx.accounts.vault.key(),
+        authority: ctx.accounts.authority.key(),
+        manager: args.manager,
+        id: args.id,
+        vault_type: args.vault_type,
+        timestamp: now,
+    });
 
     Ok(())
 }
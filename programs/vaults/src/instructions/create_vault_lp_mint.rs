@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+use crate::{
+    check, error::ErrorCode, TokenInfo, Vault, VaultLpMintCreated, VaultType, VAULT_LP_TOKEN_SEED,
+};
+
+#[derive(Accounts)]
+#[instruction(decimals: u8)]
+pub struct CreateVaultLpMint<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        init,
+        seeds = [
+            VAULT_LP_TOKEN_SEED,
+            vault.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        mint::authority = vault.key(),
+        mint::decimals = decimals,
+    )]
+    pub lp_token_mint: Box<Account<'info, Mint>>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> CreateVaultLpMint<'info> {
+    /// We need to validate that this [`Vault`] is of [`VaultType::MultiToken`]
+    /// and that none of its SPL Tokens have already issued their own LP tokens.
+    pub fn validate(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        check!(
+            vault.vault_type() == VaultType::MultiToken,
+            InvalidVaultType
+        );
+        for token_info in vault.token_infos() {
+            check!(token_info.token_supply == 0, TokenWithLpSupply);
+        }
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<CreateVaultLpMint>, _decimals: u8) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let lp_token_mint = ctx.accounts.lp_token_mint.key();
+    ctx.accounts.vault.load_mut()?.lp_info = TokenInfo::new_vault_lp(lp_token_mint);
+
+    emit!(VaultLpMintCreated {
+        vault: ctx.accounts.vault.key(),
+        lp_mint: lp_token_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -7,15 +7,24 @@ use cypher_client::{
 };
 
 use crate::{
+    check,
     error::ErrorCode,
-    state::{Vault, VAULT_SEED},
+    events::{Deposited, FeesAccrued},
+    state::{DepositorPosition, Vault, DEPOSITOR_POSITION_SEED, LOCKUP_ESCROW_SEED, VAULT_SEED},
 };
 
+/// The accounts used to deposit into a [`Vault`].
+///
+/// The [`Vault`]'s strategy sub accounts are passed as remaining accounts, see [`Vault::load_sub_accounts`].
 #[derive(Accounts)]
 pub struct Deposit<'info> {
-    #[account(mut)]
-    pub vault: Box<Account<'info, Vault>>,
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
 
+    #[account(mut)]
     pub lp_mint: Box<Account<'info, Mint>>,
 
     #[account(
@@ -26,6 +35,40 @@ pub struct Deposit<'info> {
     )]
     pub lp_token_account: Box<Account<'info, TokenAccount>>,
 
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = vault.load()?.fee_recipient,
+    )]
+    pub fee_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            DEPOSITOR_POSITION_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = DepositorPosition::LEN,
+    )]
+    pub depositor_position: Box<Account<'info, DepositorPosition>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            LOCKUP_ESCROW_SEED,
+            depositor_position.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub lockup_escrow_token_account: Box<Account<'info, TokenAccount>>,
+
     pub cache_account: AccountLoader<'info, CacheAccount>,
 
     pub clearing: AccountLoader<'info, Clearing>,
@@ -64,19 +107,134 @@ pub struct Deposit<'info> {
     pub token_program: Program<'info, Token>,
 
     pub cypher_program: Program<'info, Cypher>,
+
+    pub rent: Sysvar<'info, Rent>,
 }
 
 impl<'info> Deposit<'info> {
-    /// We need to validate that we have the correct SPL Token.
-    pub fn validate(&self) -> Result<()> {
-        self.vault
+    /// Gets the accounts shared with `deposit_for`.
+    pub fn deposit_accounts(&mut self) -> DepositAccounts<'_, 'info> {
+        DepositAccounts {
+            vault: &self.vault,
+            lp_mint: &self.lp_mint,
+            lp_token_account: &self.lp_token_account,
+            fee_token_account: &self.fee_token_account,
+            depositor_position: &mut self.depositor_position,
+            lockup_escrow_token_account: &self.lockup_escrow_token_account,
+            cache_account: &self.cache_account,
+            clearing: &self.clearing,
+            cypher_account: &self.cypher_account,
+            cypher_sub_account: &self.cypher_sub_account,
+            pool: &self.pool,
+            pool_node: &self.pool_node,
+            token_vault: &self.token_vault,
+            source_token_account: &self.source_token_account,
+            token_mint: &self.token_mint,
+            token_program: &self.token_program,
+            cypher_program: &self.cypher_program,
+        }
+    }
+}
+
+/// The accounts shared by `deposit` and `deposit_for`, which only differ in who funds the deposit and who owns it.
+pub struct DepositAccounts<'a, 'info> {
+    pub vault: &'a AccountLoader<'info, Vault>,
+    pub lp_mint: &'a Account<'info, Mint>,
+    pub lp_token_account: &'a Account<'info, TokenAccount>,
+    pub fee_token_account: &'a Account<'info, TokenAccount>,
+    pub depositor_position: &'a mut Account<'info, DepositorPosition>,
+    pub lockup_escrow_token_account: &'a Account<'info, TokenAccount>,
+    pub cache_account: &'a AccountLoader<'info, CacheAccount>,
+    pub clearing: &'a AccountLoader<'info, Clearing>,
+    pub cypher_account: &'a AccountLoader<'info, CypherAccount>,
+    pub cypher_sub_account: &'a AccountLoader<'info, CypherSubAccount>,
+    pub pool: &'a AccountLoader<'info, Pool>,
+    pub pool_node: &'a AccountLoader<'info, PoolNode>,
+    pub token_vault: &'a Account<'info, TokenAccount>,
+    pub source_token_account: &'a Account<'info, TokenAccount>,
+    pub token_mint: &'a Account<'info, Mint>,
+    pub token_program: &'a Program<'info, Token>,
+    pub cypher_program: &'a Program<'info, Cypher>,
+}
+
+impl<'a, 'info> DepositAccounts<'a, 'info> {
+    /// We need to validate that we have the correct SPL Token and that the deposit is within the limits.
+    pub fn validate(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        deposit_amount: u64,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let token_info = vault
             .get_token_info(self.token_mint.key())
             .ok_or(ErrorCode::InvalidTokenMint)?;
+        let share_info = vault.get_share_info(self.token_mint.key()).unwrap();
+        check!(self.lp_mint.key() == share_info.lp_mint, InvalidLpMint);
+
+        check!(vault.pause_state().allows_deposits(), VaultPaused);
+        check!(!vault.is_winding_down(), VaultWindingDown);
+        check!(!vault.is_epoch_mode(), EpochModeEnabled);
+        check!(token_info.is_enabled(), DepositsDisabled);
+        check!(
+            token_info.deposits.saturating_add(deposit_amount) <= token_info.deposit_limit,
+            DepositLimitExceeded
+        );
+
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        let deposit_value = Vault::get_quote_value(token_info, deposit_amount, &cache_account);
+        check!(
+            !vault.exceeds_quote_deposit_limit(deposit_value, &sub_accounts, &cache_account),
+            VaultDepositLimitExceeded
+        );
+
         Ok(())
     }
 
+    /// Gets the marked to market value backing the LP token of the SPL Token being deposited.
+    pub fn get_share_net_assets(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let vault = self.vault.load()?;
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        vault.get_share_net_assets(&self.token_mint.key(), &sub_accounts, &cache_account)
+    }
+
+    /// Converts an amount of the SPL Token being deposited into the units its LP token is priced in.
+    pub fn to_share_units(&self, amount: u64) -> Result<u64> {
+        let vault = self.vault.load()?;
+        let cache_account = self.cache_account.load()?;
+        let token_info = vault.get_token_info(self.token_mint.key()).unwrap();
+        vault.to_share_units(token_info, amount, &cache_account)
+    }
+
+    /// Gets the lockup period of the SPL Token being deposited.
+    pub fn lockup_period(&self) -> Result<i64> {
+        let vault = self.vault.load()?;
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        Ok(token_info.lockup_period)
+    }
+
     /// Deposit the input amount to the [`cypher_client::CypherAccount`].
     pub fn invoke_deposit_funds(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
         let cpi_program = self.cypher_program.to_account_info();
         let cpi_accounts = DepositFunds {
             clearing: self.clearing.to_account_info(),
@@ -97,20 +255,22 @@ impl<'info> Deposit<'info> {
                 cpi_accounts,
                 &[&[
                     VAULT_SEED,
-                    self.vault.authority.as_ref(),
-                    self.vault.id.to_le_bytes().as_ref(),
-                    &[self.vault.bump],
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
                 ]],
             ),
             amount,
         )
     }
 
-    pub fn invoke_mint_to(&self, amount: u64) -> Result<()> {
+    /// Mint the given amount of LP tokens to the destination token account.
+    pub fn invoke_mint_to(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
         let cpi_program = self.token_program.to_account_info();
         let cpi_accounts = MintTo {
             mint: self.lp_mint.to_account_info(),
-            to: self.lp_token_account.to_account_info(),
+            to,
             authority: self.vault.to_account_info(),
         };
         mint_to(
@@ -119,43 +279,126 @@ impl<'info> Deposit<'info> {
                 cpi_accounts,
                 &[&[
                     VAULT_SEED,
-                    self.vault.authority.as_ref(),
-                    self.vault.id.to_le_bytes().as_ref(),
-                    &[self.vault.bump],
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
                 ]],
             ),
             amount,
         )
     }
-}
 
-/// The user wants to deposit a token amount represented by `deposit_amount`,
-/// taking this number we need to calculate how many tokens we are going to mint for the user.
-pub fn handler(ctx: Context<Deposit>, deposit_amount: u64) -> Result<()> {
-    ctx.accounts.validate()?;
+    /// Deposits `deposit_amount` into the [`Vault`] and mints the LP tokens to `owner`, recording it in their [`DepositorPosition`].
+    ///
+    /// The deposit fails if fewer than `min_lp_out` LP tokens would be minted. While the SPL Token has a lockup period
+    /// the LP tokens are held in the owner's lockup escrow, and the owner's lockup period restarts if `restart_lockup` is set.
+    pub fn process(
+        self,
+        remaining_accounts: &[AccountInfo<'info>],
+        owner: Pubkey,
+        position_bump: u8,
+        deposit_amount: u64,
+        min_lp_out: u64,
+        restart_lockup: bool,
+    ) -> Result<()> {
+        self.validate(remaining_accounts, deposit_amount)?;
+
+        // value the [`Vault`]'s holdings before the deposit lands in the [`CypherSubAccount`]
+        let net_assets = self.get_share_net_assets(remaining_accounts)?;
+        let share_amount = self.to_share_units(deposit_amount)?;
+
+        // accrue the fees up until now so the depositor does not pay for them
+        let now = Clock::get()?.unix_timestamp;
+        let token_mint = self.token_mint.key();
+        let fee_amount = self
+            .vault
+            .load_mut()?
+            .accrue_fees(token_mint, net_assets, now)?;
+        if fee_amount != 0 {
+            self.invoke_mint_to(self.fee_token_account.to_account_info(), fee_amount)?;
+        }
+        let share_price = self.vault.load()?.get_share_price(token_mint, net_assets)?;
+        emit!(FeesAccrued {
+            vault: self.vault.key(),
+            token_mint,
+            fee_recipient: self.vault.load()?.fee_recipient,
+            lp_minted: fee_amount,
+            share_price,
+            timestamp: now,
+        });
+
+        let mint_amount: u64 = self
+            .vault
+            .load()?
+            .get_share_info(token_mint)
+            .unwrap()
+            .calculate_mint_amount(share_amount, net_assets)?;
+        check!(mint_amount != 0, ZeroAmount);
+        check!(mint_amount >= min_lp_out, SlippageExceeded);
+
+        // perform the deposit into the [`Vault`]'s [`CypherAccount`]
+        self.invoke_deposit_funds(deposit_amount)?;
 
-    // perform the deposit into the [`Vault`]'s [`CypherAccount`]
-    ctx.accounts.invoke_deposit_funds(deposit_amount)?;
+        // mint the appropriate amount of LP tokens to the owner, held in escrow while the SPL Token has a lockup period
+        let lp_token_account = if self.lockup_period()? > 0 {
+            self.lockup_escrow_token_account.to_account_info()
+        } else {
+            self.lp_token_account.to_account_info()
+        };
+        self.invoke_mint_to(lp_token_account, mint_amount)?;
 
-    let mint_amount: u64 = ctx
-        .accounts
-        .vault
-        .get_token_info(ctx.accounts.token_mint.key())
-        .unwrap()
-        .calculate_mint_amount(deposit_amount as u128)
-        .try_into()
-        .unwrap();
+        self.vault.load_mut()?.record_deposit(
+            token_mint,
+            deposit_amount,
+            share_amount,
+            mint_amount,
+        )?;
 
-    // mint the appropriate amount of LP tokens to the end user
-    ctx.accounts.invoke_mint_to(mint_amount)?;
+        // record the deposit in the owner's position
+        let vault = self.vault.key();
+        let depositor_position = self.depositor_position;
+        if depositor_position.owner == Pubkey::default() {
+            depositor_position.init(vault, token_mint, owner, position_bump);
+        }
+        depositor_position.record_deposit(deposit_amount, share_amount, mint_amount);
+        if restart_lockup {
+            depositor_position.restart_lockup(now);
+        }
 
-    let vault = &mut ctx.accounts.vault;
-    let token_info = vault
-        .get_token_info_mut(ctx.accounts.token_mint.key())
-        .unwrap();
+        emit!(Deposited {
+            vault,
+            token_mint,
+            owner,
+            amount: deposit_amount,
+            lp_minted: mint_amount,
+            share_price,
+            timestamp: now,
+        });
 
-    token_info.deposits += deposit_amount;
-    token_info.token_supply += mint_amount;
+        Ok(())
+    }
+}
 
-    Ok(())
+/// The user wants to deposit a token amount represented by `deposit_amount`,
+/// taking this number we need to calculate how many tokens we are going to mint for the user.
+///
+/// The deposit fails if fewer than `min_lp_out` LP tokens would be minted.
+///
+/// While the SPL Token has a lockup period the LP tokens are held in the depositor's lockup escrow,
+/// which restarts the lockup period of everything in it, until they are released by `release_lockup`.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
+    deposit_amount: u64,
+    min_lp_out: u64,
+) -> Result<()> {
+    let owner = ctx.accounts.authority.key();
+    let position_bump = *ctx.bumps.get("depositor_position").unwrap();
+    ctx.accounts.deposit_accounts().process(
+        ctx.remaining_accounts,
+        owner,
+        position_bump,
+        deposit_amount,
+        min_lp_out,
+        true,
+    )
 }
@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use cypher_client::{
+    program::Cypher, CacheAccount, Clearing, CypherAccount, CypherSubAccount, Pool, PoolNode,
+};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    instructions::DepositAccounts,
+    state::{DepositorPosition, Vault, DEPOSITOR_POSITION_SEED, LOCKUP_ESCROW_SEED},
+};
+
+/// The accounts used to deposit into a [`Vault`] on behalf of a beneficiary.
+///
+/// The [`Vault`]'s strategy sub accounts are passed as remaining accounts, see [`Vault::load_sub_accounts`].
+#[derive(Accounts)]
+#[instruction(beneficiary: Pubkey)]
+pub struct DepositFor<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = beneficiary,
+    )]
+    pub lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = vault.load()?.fee_recipient,
+    )]
+    pub fee_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            DEPOSITOR_POSITION_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            beneficiary.as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = DepositorPosition::LEN,
+    )]
+    pub depositor_position: Box<Account<'info, DepositorPosition>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            LOCKUP_ESCROW_SEED,
+            depositor_position.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub lockup_escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    #[account(mut)]
+    pub cypher_account: AccountLoader<'info, CypherAccount>,
+
+    #[account(mut)]
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(mut)]
+    pub pool_node: AccountLoader<'info, PoolNode>,
+
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = funder,
+    )]
+    pub source_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    pub funder: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub cypher_program: Program<'info, Cypher>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> DepositFor<'info> {
+    /// We need to validate that the beneficiary approved deposits on their behalf if they restart their lockup period.
+    pub fn validate(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        check!(
+            token_info.lockup_period == 0 || self.depositor_position.approves_deposits_for,
+            DepositForNotApproved
+        );
+        Ok(())
+    }
+
+    /// Gets the accounts shared with `deposit`.
+    pub fn deposit_accounts(&mut self) -> DepositAccounts<'_, 'info> {
+        DepositAccounts {
+            vault: &self.vault,
+            lp_mint: &self.lp_mint,
+            lp_token_account: &self.lp_token_account,
+            fee_token_account: &self.fee_token_account,
+            depositor_position: &mut self.depositor_position,
+            lockup_escrow_token_account: &self.lockup_escrow_token_account,
+            cache_account: &self.cache_account,
+            clearing: &self.clearing,
+            cypher_account: &self.cypher_account,
+            cypher_sub_account: &self.cypher_sub_account,
+            pool: &self.pool,
+            pool_node: &self.pool_node,
+            token_vault: &self.token_vault,
+            source_token_account: &self.source_token_account,
+            token_mint: &self.token_mint,
+            token_program: &self.token_program,
+            cypher_program: &self.cypher_program,
+        }
+    }
+}
+
+/// The funder wants to deposit a token amount represented by `deposit_amount` on behalf of `beneficiary`,
+/// the funder signs for the SPL Tokens while the LP tokens and the [`DepositorPosition`] belong to the beneficiary.
+///
+/// The deposit fails if fewer than `min_lp_out` LP tokens would be minted.
+///
+/// While the SPL Token has a lockup period the LP tokens go to the beneficiary's lockup escrow and restart its
+/// lockup period, so this is only allowed once the beneficiary approved it with `approve_deposits_for`.
+/// Without a lockup period the beneficiary's lockup clock is left untouched.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositFor<'info>>,
+    beneficiary: Pubkey,
+    deposit_amount: u64,
+    min_lp_out: u64,
+) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let position_bump = *ctx.bumps.get("depositor_position").unwrap();
+    let restart_lockup = ctx.accounts.deposit_accounts().lockup_period()? > 0;
+    ctx.accounts.deposit_accounts().process(
+        ctx.remaining_accounts,
+        beneficiary,
+        position_bump,
+        deposit_amount,
+        min_lp_out,
+        restart_lockup,
+    )
+}
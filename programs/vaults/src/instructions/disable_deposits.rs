@@ -1,14 +1,15 @@
 use anchor_lang::prelude::*;
 
-use crate::Vault;
+use crate::{error::ErrorCode, DepositsDisabled, Vault};
 
 #[derive(Accounts)]
 pub struct DisableDeposits<'info> {
     #[account(
         mut,
-        has_one = authority,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
     )]
-    pub vault: Box<Account<'info, Vault>>,
+    pub vault: AccountLoader<'info, Vault>,
 
     pub authority: Signer<'info>,
 }
@@ -16,13 +17,21 @@ pub struct DisableDeposits<'info> {
 impl<'info> DisableDeposits<'info> {
     /// Disables deposits for the given SPL Token Mint.
     fn disable_deposits(&mut self, token_mint: Pubkey) -> Result<()> {
-        let token_info = self.vault.get_token_info_mut(token_mint).unwrap();
-        token_info.enabled = false;
+        let mut vault = self.vault.load_mut()?;
+        let token_info = vault.get_token_info_mut(token_mint).unwrap();
+        token_info.set_enabled(false);
         Ok(())
     }
 }
 
 pub fn handler(ctx: Context<DisableDeposits>, token_mint: Pubkey) -> Result<()> {
     ctx.accounts.disable_deposits(token_mint)?;
+
+    emit!(DepositsDisabled {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
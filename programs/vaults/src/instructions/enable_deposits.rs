@@ -1,14 +1,15 @@
 use anchor_lang::prelude::*;
 
-use crate::Vault;
+use crate::{error::ErrorCode, DepositsEnabled, Vault};
 
 #[derive(Accounts)]
 pub struct EnableDeposits<'info> {
     #[account(
         mut,
-        has_one = authority,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
     )]
-    pub vault: Box<Account<'info, Vault>>,
+    pub vault: AccountLoader<'info, Vault>,
 
     pub authority: Signer<'info>,
 }
@@ -16,13 +17,21 @@ pub struct EnableDeposits<'info> {
 impl<'info> EnableDeposits<'info> {
     /// Enables deposits for the given SPL Token Mint.
     fn enable_deposits(&mut self, token_mint: Pubkey) -> Result<()> {
-        let token_info = self.vault.get_token_info_mut(token_mint).unwrap();
-        token_info.enabled = true;
+        let mut vault = self.vault.load_mut()?;
+        let token_info = vault.get_token_info_mut(token_mint).unwrap();
+        token_info.set_enabled(true);
         Ok(())
     }
 }
 
 pub fn handler(ctx: Context<EnableDeposits>, token_mint: Pubkey) -> Result<()> {
     ctx.accounts.enable_deposits(token_mint)?;
+
+    emit!(DepositsEnabled {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
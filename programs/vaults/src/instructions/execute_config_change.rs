@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use cypher_client::Clearing;
+
+use crate::{
+    check, error::ErrorCode, AuthorityProposed, ConfigChange, ConfigChangeExecuted,
+    DepositLimitSet, FeesSet, GuardianSet, LockupPeriodSet, ManagerSet, MaxWithdrawWaitSet,
+    PendingConfigChange, RiskParamsSet, Vault, VaultDepositLimitSet,
+};
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        close = authority,
+    )]
+    pub pending_config_change: Box<Account<'info, PendingConfigChange>>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+impl<'info> ExecuteConfigChange<'info> {
+    /// We need to validate that the timelock has passed and that the change still applies to the [`Vault`].
+    pub fn validate(&self, now: i64) -> Result<()> {
+        check!(
+            self.pending_config_change.is_executable(now),
+            ConfigChangeNotExecutable
+        );
+        self.pending_config_change
+            .change
+            .validate(&self.vault.load()?, &self.clearing.load()?)
+    }
+}
+
+/// The authority executes a queued [`ConfigChange`] once the [`Vault`]'s timelock has passed.
+///
+/// The same event as the corresponding direct setter is emitted so indexers see a single history.
+///
+/// A [`ConfigChange::Fees`] expects the fees of every LP token to be accrued in the same transaction beforehand,
+/// see [`crate::instructions::accrue_fees`].
+pub fn handler(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.validate(now)?;
+
+    let change = ctx.accounts.pending_config_change.change;
+    ctx.accounts
+        .vault
+        .load_mut()?
+        .apply_config_change(&change, now)?;
+
+    let vault = ctx.accounts.vault.key();
+    match change {
+        ConfigChange::DepositLimit {
+            token_mint,
+            deposit_limit,
+        } => emit!(DepositLimitSet {
+            vault,
+            token_mint,
+            deposit_limit,
+            timestamp: now,
+        }),
+        ConfigChange::Fees { args } => emit!(FeesSet {
+            vault,
+            fee_recipient: args.fee_recipient,
+            management_fee_bps: args.management_fee_bps,
+            performance_fee_bps: args.performance_fee_bps,
+            timestamp: now,
+        }),
+        ConfigChange::Manager { manager } => emit!(ManagerSet {
+            vault,
+            manager,
+            timestamp: now,
+        }),
+        ConfigChange::Guardian { guardian } => emit!(GuardianSet {
+            vault,
+            guardian,
+            timestamp: now,
+        }),
+        ConfigChange::PendingAuthority { pending_authority } => emit!(AuthorityProposed {
+            vault,
+            authority: ctx.accounts.authority.key(),
+            pending_authority,
+            timestamp: now,
+        }),
+        ConfigChange::ConfigTimelock { .. } => {}
+        ConfigChange::RiskParams {
+            sub_account_number,
+            risk_params,
+        } => emit!(RiskParamsSet {
+            vault,
+            sub_account_number,
+            risk_params,
+            timestamp: now,
+        }),
+        ConfigChange::VaultDepositLimit {
+            quote_deposit_limit,
+        } => emit!(VaultDepositLimitSet {
+            vault,
+            quote_deposit_limit,
+            timestamp: now,
+        }),
+        ConfigChange::LockupPeriod {
+            token_mint,
+            lockup_period,
+        } => emit!(LockupPeriodSet {
+            vault,
+            token_mint,
+            lockup_period,
+            timestamp: now,
+        }),
+        ConfigChange::MaxWithdrawWait { max_withdraw_wait } => emit!(MaxWithdrawWaitSet {
+            vault,
+            max_withdraw_wait,
+            timestamp: now,
+        }),
+    }
+
+    emit!(ConfigChangeExecuted {
+        vault,
+        pending_config_change: ctx.accounts.pending_config_change.key(),
+        change,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+
+use cypher_client::Pool;
+
+use crate::{
+    check, error::ErrorCode, MigrateVaultArgs, Vault, VaultMigrated, VaultV0, LP_TOKEN_SEED,
+    VAULT_SEED, VAULT_VERSION,
+};
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    /// CHECK: The [`Vault`] can not be deserialized before it is migrated, it is checked in [`MigrateVault::validate`].
+    #[account(
+        mut,
+        owner = crate::id(),
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> MigrateVault<'info> {
+    /// We need to validate that this is an unmigrated [`Vault`] of this program and that the signer is its authority,
+    /// returning the [`Vault`] in its original layout.
+    pub fn validate(&self, args: &MigrateVaultArgs) -> Result<VaultV0> {
+        let data = self.vault.try_borrow_data()?;
+        check!(
+            data.len() > 8 && data[..8] == Vault::discriminator(),
+            InvalidVaultAccount
+        );
+        check!(data[8] < VAULT_VERSION, VaultAlreadyMigrated);
+
+        let v0 = VaultV0::deserialize(&mut &data[8..])?;
+        let vault = Pubkey::create_program_address(
+            &[
+                VAULT_SEED,
+                v0.authority.as_ref(),
+                args.id.to_le_bytes().as_ref(),
+                &[v0.bump],
+            ],
+            &crate::id(),
+        )
+        .map_err(|_| error!(ErrorCode::InvalidVaultAccount))?;
+        check!(self.vault.key() == vault, InvalidVaultAccount);
+        check!(self.authority.key() == v0.authority, InvalidAuthority);
+
+        Ok(v0)
+    }
+
+    /// Gets the index of the price cache of each of the [`Vault`]'s SPL Tokens from their Cypher pools,
+    /// which are passed as remaining accounts in the order of the original layout's SPL Tokens.
+    pub fn get_cache_indexes(
+        &self,
+        v0: &VaultV0,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<Vec<u8>> {
+        check!(
+            remaining_accounts.len() == v0.token_infos.len(),
+            InvalidTokenMint
+        );
+        v0.token_infos
+            .iter()
+            .zip(remaining_accounts.iter())
+            .map(|(ti, account_info)| {
+                let pool_loader = AccountLoader::<Pool>::try_from(account_info)?;
+                let pool = pool_loader.load()?;
+                check!(pool.token_mint == ti.token_mint, InvalidTokenMint);
+                Ok(pool.config.cache_index)
+            })
+            .collect()
+    }
+
+    /// Resizes the [`Vault`] to fit the current layout, funding the rent with the payer.
+    pub fn resize_vault(&self, new_size: usize) -> Result<()> {
+        let vault_info = self.vault.to_account_info();
+        if vault_info.data_len() >= new_size {
+            return Ok(());
+        }
+
+        let minimum_balance = self.rent.minimum_balance(new_size);
+        let lamports_needed = minimum_balance.saturating_sub(vault_info.lamports());
+        if lamports_needed != 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.payer.to_account_info(),
+                        to: vault_info.clone(),
+                    },
+                ),
+                lamports_needed,
+            )?;
+        }
+
+        vault_info.realloc(new_size, false)?;
+
+        Ok(())
+    }
+}
+
+/// The authority migrates a [`Vault`] created with an older layout to the current [`VAULT_VERSION`],
+/// after which the other instructions can operate on it again.
+///
+/// The Cypher pools of the [`Vault`]'s SPL Tokens are passed as remaining accounts, see [`MigrateVault::get_cache_indexes`].
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateVault<'info>>,
+    args: MigrateVaultArgs,
+) -> Result<()> {
+    let v0 = ctx.accounts.validate(&args)?;
+    let from_version = v0.version;
+    let cache_indexes = ctx
+        .accounts
+        .get_cache_indexes(&v0, ctx.remaining_accounts)?;
+
+    // the original layout issued a single LP token per vault
+    let vault_key = ctx.accounts.vault.key();
+    let (lp_mint, _) =
+        Pubkey::find_program_address(&[LP_TOKEN_SEED, vault_key.as_ref()], &crate::id());
+
+    ctx.accounts.resize_vault(Vault::LEN)?;
+
+    // clear the original layout before writing the current one in its place
+    let vault_info = ctx.accounts.vault.to_account_info();
+    vault_info.try_borrow_mut_data()?[8..].fill(0);
+
+    let vault_loader = AccountLoader::<Vault>::try_from(&vault_info)?;
+    vault_loader
+        .load_mut()?
+        .init_from_v0(&v0, lp_mint, &args, &cache_indexes)?;
+
+    emit!(VaultMigrated {
+        vault: vault_key,
+        from_version,
+        to_version: VAULT_VERSION,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,19 +1,95 @@
+pub mod accept_authority;
+pub mod accrue_fees;
+pub mod approve_deposits_for;
+pub mod cancel_all_orders;
+pub mod cancel_config_change;
+pub mod cancel_epoch_ticket;
+pub mod cancel_order;
+pub mod claim_epoch;
+pub mod claim_wind_down;
 pub mod close_deposits;
 pub mod close_vault;
+pub mod create_strategy_sub_account;
 pub mod create_vault;
+pub mod create_vault_lp_mint;
 pub mod deposit;
+pub mod deposit_for;
 pub mod disable_deposits;
 pub mod enable_deposits;
+pub mod execute_config_change;
+pub mod migrate_vault;
+pub mod new_order;
 pub mod open_deposits;
+pub mod pause;
+pub mod process_withdraw;
+pub mod propose_authority;
+pub mod queue_config_change;
+pub mod queue_deposit;
+pub mod queue_withdraw;
+pub mod redeem;
+pub mod release_lockup;
+pub mod request_withdraw;
+pub mod roll_epoch;
 pub mod set_deposit_limit;
+pub mod set_fees;
+pub mod set_guardian;
+pub mod set_lockup_period;
+pub mod set_manager;
+pub mod set_max_withdraw_wait;
+pub mod set_risk_params;
+pub mod set_vault_deposit_limit;
+pub mod settle_funds;
+pub mod sweep_dust;
+pub mod trade;
+pub mod transfer_collateral;
+pub mod unpause;
+pub mod wind_down;
 pub mod withdraw;
 
+pub use accept_authority::*;
+pub use accrue_fees::*;
+pub use approve_deposits_for::*;
+pub use cancel_all_orders::*;
+pub use cancel_config_change::*;
+pub use cancel_epoch_ticket::*;
+pub use cancel_order::*;
+pub use claim_epoch::*;
+pub use claim_wind_down::*;
 pub use close_deposits::*;
 pub use close_vault::*;
+pub use create_strategy_sub_account::*;
 pub use create_vault::*;
+pub use create_vault_lp_mint::*;
 pub use deposit::*;
+pub use deposit_for::*;
 pub use disable_deposits::*;
 pub use enable_deposits::*;
+pub use execute_config_change::*;
+pub use migrate_vault::*;
+pub use new_order::*;
 pub use open_deposits::*;
+pub use pause::*;
+pub use process_withdraw::*;
+pub use propose_authority::*;
+pub use queue_config_change::*;
+pub use queue_deposit::*;
+pub use queue_withdraw::*;
+pub use redeem::*;
+pub use release_lockup::*;
+pub use request_withdraw::*;
+pub use roll_epoch::*;
 pub use set_deposit_limit::*;
+pub use set_fees::*;
+pub use set_guardian::*;
+pub use set_lockup_period::*;
+pub use set_manager::*;
+pub use set_max_withdraw_wait::*;
+pub use set_risk_params::*;
+pub use set_vault_deposit_limit::*;
+pub use settle_funds::*;
+pub use sweep_dust::*;
+pub use trade::*;
+pub use transfer_collateral::*;
+pub use unpause::*;
+pub use wind_down::*;
 pub use withdraw::*;
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{NewOrderArgs, Trade, TradeOperation};
+
+/// Places a new order on a Cypher market, signed by the [`crate::Vault`].
+///
+/// The market's accounts are passed as remaining accounts, see [`crate::DerivativeMarketAccounts`] and [`crate::SpotMarketAccounts`].
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+    args: NewOrderArgs,
+) -> Result<()> {
+    ctx.accounts.validate(TradeOperation::NewOrder)?;
+
+    let market_type = args.market_type();
+    let position_sizes = ctx.accounts.get_position_sizes()?;
+    let equity = ctx.accounts.get_equity()?;
+    ctx.accounts
+        .invoke_new_order(ctx.remaining_accounts, args)?;
+    ctx.accounts
+        .emit_trade_executed(market_type, TradeOperation::NewOrder)?;
+
+    // a winding down [`crate::Vault`] can only reduce its positions
+    ctx.accounts.check_positions_reduced(&position_sizes)?;
+
+    // anyone closing the positions of an overdue wind-down can not do so at any price
+    ctx.accounts.check_settlement_slippage(equity)?;
+
+    // the trade must not leave the [`crate::Vault`] in breach of its risk limits
+    ctx.accounts.check_risk_limits()
+}
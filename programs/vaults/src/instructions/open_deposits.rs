@@ -1,22 +1,28 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token};
+use cypher_client::Pool;
 
-use crate::{check, OpenDepositsArgs, Vault, VaultType, LP_TOKEN_SEED};
+use crate::{
+    check, error::ErrorCode, DepositsOpened, OpenDepositsArgs, TokenInfo, Vault, VaultType,
+    LP_TOKEN_SEED,
+};
 
 #[derive(Accounts)]
 #[instruction(args: OpenDepositsArgs)]
 pub struct OpenDeposits<'info> {
     #[account(
         mut,
-        has_one = authority,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
     )]
-    pub vault: Box<Account<'info, Vault>>,
+    pub vault: AccountLoader<'info, Vault>,
 
     #[account(
         init,
         seeds = [
             LP_TOKEN_SEED,
-            vault.key().as_ref()
+            vault.key().as_ref(),
+            args.token_mint.as_ref(),
         ],
         bump,
         payer = payer,
@@ -25,6 +31,8 @@ pub struct OpenDeposits<'info> {
     )]
     pub lp_token_mint: Box<Account<'info, Mint>>,
 
+    pub pool: AccountLoader<'info, Pool>,
+
     pub authority: Signer<'info>,
 
     #[account(mut)]
@@ -38,21 +46,49 @@ pub struct OpenDeposits<'info> {
 }
 
 impl<'info> OpenDeposits<'info> {
-    /// We need to validate that this [`Vault`] is of [`VaultType::MultiToken`].
-    pub fn validate(&self) -> Result<()> {
+    /// We need to validate that this [`Vault`] can accept another SPL Token.
+    ///
+    /// A [`VaultType::SingleToken`] vault accepts exactly one SPL Token and
+    /// no SPL Token can be accepted twice.
+    pub fn validate(&self, args: &OpenDepositsArgs) -> Result<()> {
+        let vault = self.vault.load()?;
         check!(
-            self.vault.vault_type == VaultType::MultiToken,
+            vault.vault_type() == VaultType::MultiToken || vault.token_info_count == 0,
             InvalidVaultType
         );
-        Ok(())
-    }
+        check!(
+            vault.get_token_info(args.token_mint).is_none(),
+            TokenAlreadySupported
+        );
+        check!(args.lockup_period >= 0, InvalidLockupPeriod);
 
-    /// Resizes the [`Vault`] account to support new SPL Tokens.
-    pub fn resize_vault(&self) -> Result<()> {
+        // the SPL Token is valued with the price cache of its Cypher pool
+        let pool = self.pool.load()?;
+        check!(pool.token_mint == args.token_mint, InvalidTokenMint);
+        check!(
+            pool.config.cache_index == args.cache_index,
+            InvalidCacheIndex
+        );
         Ok(())
     }
 }
 
 pub fn handler(ctx: Context<OpenDeposits>, args: OpenDepositsArgs) -> Result<()> {
+    ctx.accounts.validate(&args)?;
+
+    let lp_token_mint = ctx.accounts.lp_token_mint.key();
+    ctx.accounts
+        .vault
+        .load_mut()?
+        .add_token_info(TokenInfo::new(&args, lp_token_mint))?;
+
+    emit!(DepositsOpened {
+        vault: ctx.accounts.vault.key(),
+        token_mint: args.token_mint,
+        lp_mint: lp_token_mint,
+        deposit_limit: args.deposit_limit,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
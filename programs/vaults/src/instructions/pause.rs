@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{check, error::ErrorCode, PauseState, PauseStateSet, Vault};
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub signer: Signer<'info>,
+}
+
+impl<'info> Pause<'info> {
+    /// Validate that the signer can move the [`Vault`] into the given [`PauseState`].
+    ///
+    /// The authority can pause the [`Vault`] in any way, the guardian can only make the pause stricter.
+    pub fn validate(&self, pause_state: PauseState) -> Result<()> {
+        let vault = self.vault.load()?;
+        let signer = self.signer.key();
+        if signer == vault.authority {
+            return Ok(());
+        }
+        check!(
+            vault.guardian != Pubkey::default() && signer == vault.guardian,
+            InvalidGuardian
+        );
+        check!(pause_state >= vault.pause_state(), InvalidPauseState);
+        Ok(())
+    }
+}
+
+/// The guardian or the authority pauses the [`Vault`], blocking deposits and trading.
+///
+/// Withdrawals remain open if `withdraw_only` is set, otherwise they are blocked as well.
+pub fn handler(ctx: Context<Pause>, withdraw_only: bool) -> Result<()> {
+    let pause_state = if withdraw_only {
+        PauseState::WithdrawOnly
+    } else {
+        PauseState::Paused
+    };
+
+    ctx.accounts.validate(pause_state)?;
+
+    ctx.accounts.vault.load_mut()?.set_pause_state(pause_state);
+
+    emit!(PauseStateSet {
+        vault: ctx.accounts.vault.key(),
+        signer: ctx.accounts.signer.key(),
+        pause_state,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
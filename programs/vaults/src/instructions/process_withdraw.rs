@@ -0,0 +1,389 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{
+    burn, close_account, mint_to, Burn, CloseAccount, Mint, MintTo, Token, TokenAccount,
+};
+use cypher_client::{
+    cpi::{accounts::WithdrawFunds, withdraw_funds},
+    program::Cypher,
+    CacheAccount, Clearing, CypherAccount, CypherSubAccount, Pool, PoolNode,
+};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::{FeesAccrued, Withdrawn},
+    state::{
+        DepositorPosition, Vault, WithdrawTicket, DEPOSITOR_POSITION_SEED, VAULT_SEED,
+        WITHDRAW_ESCROW_SEED,
+    },
+};
+
+/// The [`Vault`]'s strategy sub accounts are passed as writable remaining accounts, see [`Vault::load_sub_accounts`].
+#[derive(Accounts)]
+pub struct ProcessWithdraw<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = vault.load()?.fee_recipient,
+    )]
+    pub fee_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        has_one = owner,
+        has_one = token_mint,
+        close = owner,
+    )]
+    pub withdraw_ticket: Box<Account<'info, WithdrawTicket>>,
+
+    #[account(
+        mut,
+        seeds = [
+            WITHDRAW_ESCROW_SEED,
+            withdraw_ticket.key().as_ref(),
+        ],
+        bump,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            DEPOSITOR_POSITION_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = depositor_position.bump,
+    )]
+    pub depositor_position: Box<Account<'info, DepositorPosition>>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    #[account(mut)]
+    pub cypher_account: AccountLoader<'info, CypherAccount>,
+
+    #[account(mut)]
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(mut)]
+    pub pool_node: AccountLoader<'info, PoolNode>,
+
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = owner,
+    )]
+    pub destination_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Checked via CPI to [`Cypher`].
+    pub vault_signer: AccountInfo<'info>,
+
+    /// CHECK: Checked via the [`WithdrawTicket`].
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+
+    pub keeper: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub cypher_program: Program<'info, Cypher>,
+}
+
+impl<'info> ProcessWithdraw<'info> {
+    /// We need to validate that we have the correct SPL Token and that the keeper can settle the [`WithdrawTicket`].
+    pub fn validate(&self, now: i64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let share_info = vault.get_share_info(self.token_mint.key()).unwrap();
+        check!(self.lp_mint.key() == share_info.lp_mint, InvalidLpMint);
+        check!(vault.pause_state().allows_withdrawals(), VaultPaused);
+        // a vault-wide LP token can only be redeemed into enabled SPL Tokens
+        check!(
+            !vault.has_vault_lp() || token_info.is_enabled(),
+            TokenDisabled
+        );
+
+        // the manager can settle at any time, anyone else only after the maximum wait
+        check!(
+            self.keeper.key() == vault.manager
+                || self
+                    .withdraw_ticket
+                    .is_expired(vault.max_withdraw_wait, now),
+            WithdrawTicketNotExpired
+        );
+        Ok(())
+    }
+
+    /// Gets the marked to market value backing the LP token of the SPL Token being withdrawn.
+    pub fn get_share_net_assets(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let vault = self.vault.load()?;
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        vault.get_share_net_assets(&self.token_mint.key(), &sub_accounts, &cache_account)
+    }
+
+    /// Splits a withdrawal across the [`Vault`]'s [`CypherSubAccount`]s, see [`Vault::get_withdraw_amounts`].
+    pub fn get_withdraw_amounts(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        amount: u64,
+    ) -> Result<Vec<(AccountInfo<'info>, u64)>> {
+        let vault = self.vault.load()?;
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        let withdraw_amounts = Vault::get_withdraw_amounts(
+            &self.token_mint.key(),
+            amount,
+            &sub_accounts,
+            &cache_account,
+        );
+        Ok(sub_account_loaders
+            .iter()
+            .map(|sa| sa.to_account_info())
+            .zip(withdraw_amounts)
+            .filter(|(_, amount)| *amount != 0)
+            .collect())
+    }
+
+    /// Converts an amount in the units the LP token is priced in into the SPL Token being withdrawn.
+    pub fn from_share_units(&self, amount: u64) -> Result<u64> {
+        let vault = self.vault.load()?;
+        let cache_account = self.cache_account.load()?;
+        let token_info = vault.get_token_info(self.token_mint.key()).unwrap();
+        vault.from_share_units(token_info, amount, &cache_account)
+    }
+
+    /// Withdraw the input amount from the [`cypher_client::CypherAccount`].
+    pub fn invoke_withdraw_funds(
+        &self,
+        sub_account: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.cypher_program.to_account_info();
+        let cpi_accounts = WithdrawFunds {
+            clearing: self.clearing.to_account_info(),
+            cache_account: self.cache_account.to_account_info(),
+            master_account: self.cypher_account.to_account_info(),
+            sub_account,
+            pool: self.pool.to_account_info(),
+            pool_node: self.pool_node.to_account_info(),
+            token_vault: self.token_vault.to_account_info(),
+            destination_token_account: self.destination_token_account.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            vault_signer: self.vault_signer.to_account_info(),
+            authority: self.vault.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        withdraw_funds(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+
+    /// Mint the accrued fees to the fee recipient.
+    pub fn invoke_mint_fees(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = MintTo {
+            mint: self.lp_mint.to_account_info(),
+            to: self.fee_token_account.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        mint_to(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+
+    /// Burn the LP tokens locked in escrow.
+    pub fn invoke_burn(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Burn {
+            mint: self.lp_mint.to_account_info(),
+            from: self.escrow_token_account.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        burn(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+
+    /// Close the LP token escrow, returning the rent to the owner.
+    pub fn invoke_close_escrow(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = CloseAccount {
+            account: self.escrow_token_account.to_account_info(),
+            destination: self.owner.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        close_account(CpiContext::new_with_signer(
+            cpi_program,
+            cpi_accounts,
+            &[&[
+                VAULT_SEED,
+                vault.creator.as_ref(),
+                vault.id.to_le_bytes().as_ref(),
+                &[vault.bump],
+            ]],
+        ))
+    }
+}
+
+/// The keeper wants to settle a [`WithdrawTicket`], the LP tokens locked in escrow are
+/// redeemed at the share price in effect now that liquidity has been freed.
+///
+/// The settlement fails if fewer than the [`WithdrawTicket`]'s `min_tokens_out` tokens would be withdrawn.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ProcessWithdraw<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.validate(now)?;
+
+    // value the [`Vault`]'s holdings before the withdrawal leaves the [`CypherSubAccount`]
+    let net_assets = ctx.accounts.get_share_net_assets(ctx.remaining_accounts)?;
+
+    // accrue the fees up until now so the withdrawer pays their share of them
+    let token_mint = ctx.accounts.token_mint.key();
+    let fee_amount = ctx
+        .accounts
+        .vault
+        .load_mut()?
+        .accrue_fees(token_mint, net_assets, now)?;
+    if fee_amount != 0 {
+        ctx.accounts.invoke_mint_fees(fee_amount)?;
+    }
+    let share_price = ctx
+        .accounts
+        .vault
+        .load()?
+        .get_share_price(token_mint, net_assets)?;
+    emit!(FeesAccrued {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        fee_recipient: ctx.accounts.vault.load()?.fee_recipient,
+        lp_minted: fee_amount,
+        share_price,
+        timestamp: now,
+    });
+
+    let burn_amount = ctx.accounts.withdraw_ticket.lp_amount;
+    let share_amount: u64 = ctx
+        .accounts
+        .vault
+        .load()?
+        .get_share_info(token_mint)
+        .unwrap()
+        .calculate_redeem_amount(burn_amount, net_assets)?;
+    let withdraw_amount = ctx.accounts.from_share_units(share_amount)?;
+    check!(
+        withdraw_amount >= ctx.accounts.withdraw_ticket.min_tokens_out,
+        SlippageExceeded
+    );
+
+    // burn the escrowed LP tokens and close the escrow
+    ctx.accounts.invoke_burn(burn_amount)?;
+    ctx.accounts.invoke_close_escrow()?;
+
+    // finally withdraw from the [`Vault`]'s [`CypherAccount`], spread across its sub accounts
+    for (sub_account, amount) in ctx
+        .accounts
+        .get_withdraw_amounts(ctx.remaining_accounts, withdraw_amount)?
+    {
+        ctx.accounts.invoke_withdraw_funds(sub_account, amount)?;
+    }
+
+    // update the [`Vault`]'s data, tickets requested before the count was kept are not part of it
+    {
+        let mut vault = ctx.accounts.vault.load_mut()?;
+        vault.record_withdraw(token_mint, withdraw_amount, burn_amount)?;
+        vault.withdraw_ticket_count = vault.withdraw_ticket_count.saturating_sub(1);
+    }
+
+    // record the withdrawal in the depositor's position
+    ctx.accounts
+        .depositor_position
+        .record_withdraw(withdraw_amount, burn_amount);
+
+    emit!(Withdrawn {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        owner: ctx.accounts.owner.key(),
+        amount: withdraw_amount,
+        lp_burned: burn_amount,
+        share_price,
+        timestamp: now,
+    });
+
+    Ok(())
+}
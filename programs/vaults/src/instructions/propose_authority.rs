@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, AuthorityProposed, Vault};
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> ProposeAuthority<'info> {
+    /// Proposes a new authority for the [`Vault`], a default public key cancels a pending transfer.
+    fn propose_authority(&mut self, pending_authority: Pubkey) -> Result<()> {
+        self.vault.load_mut()?.pending_authority = pending_authority;
+        Ok(())
+    }
+}
+
+/// The authority proposes `pending_authority` as its successor, which only takes over once it
+/// accepts through `accept_authority`, so the [`Vault`] can never be handed to a key nobody controls.
+pub fn handler(ctx: Context<ProposeAuthority>, pending_authority: Pubkey) -> Result<()> {
+    ctx.accounts.propose_authority(pending_authority)?;
+
+    emit!(AuthorityProposed {
+        vault: ctx.accounts.vault.key(),
+        authority: ctx.accounts.authority.key(),
+        pending_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use cypher_client::Clearing;
+
+use crate::{
+    error::ErrorCode, ConfigChange, ConfigChangeQueued, PendingConfigChange, Vault,
+    CONFIG_CHANGE_SEED,
+};
+
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        init,
+        seeds = [
+            CONFIG_CHANGE_SEED,
+            vault.key().as_ref(),
+            vault.load()?.config_change_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = PendingConfigChange::LEN,
+    )]
+    pub pending_config_change: Box<Account<'info, PendingConfigChange>>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// The authority queues a [`ConfigChange`], which can only be executed through `execute_config_change`
+/// once the [`Vault`]'s timelock has passed, giving depositors time to react to it.
+pub fn handler(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+    change.validate(&ctx.accounts.vault.load()?, &ctx.accounts.clearing.load()?)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let bump = *ctx.bumps.get("pending_config_change").unwrap();
+
+    let (id, config_timelock) = {
+        let mut vault = ctx.accounts.vault.load_mut()?;
+        let id = vault.config_change_count;
+        vault.config_change_count = id.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        (id, vault.config_timelock)
+    };
+
+    let vault = ctx.accounts.vault.key();
+    let pending_config_change = &mut ctx.accounts.pending_config_change;
+    pending_config_change.init(vault, id, change, bump, now, config_timelock);
+
+    emit!(ConfigChangeQueued {
+        vault,
+        pending_config_change: pending_config_change.key(),
+        change,
+        executable_at: pending_config_change.executable_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
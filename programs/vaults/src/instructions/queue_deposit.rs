@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+use cypher_client::{CacheAccount, CypherSubAccount};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::DepositQueued,
+    state::{EpochTicket, Vault, EPOCH_TICKET_SEED, EPOCH_TOKEN_ESCROW_SEED},
+};
+
+/// The [`Vault`]'s strategy sub accounts are passed as remaining accounts, see [`Vault::load_sub_accounts`].
+#[derive(Accounts)]
+pub struct QueueDeposit<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            EPOCH_TICKET_SEED,
+            vault.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = EpochTicket::LEN,
+    )]
+    pub epoch_ticket: Box<Account<'info, EpochTicket>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            EPOCH_TOKEN_ESCROW_SEED,
+            vault.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        token::mint = token_mint,
+        token::authority = vault,
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = authority,
+    )]
+    pub source_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> QueueDeposit<'info> {
+    /// We need to validate that this is an epoch [`Vault`] and that the queued deposits are within the limits.
+    pub fn validate(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        deposit_amount: u64,
+    ) -> Result<()> {
+        check!(deposit_amount != 0, ZeroAmount);
+        let vault = self.vault.load()?;
+        check!(vault.is_epoch_mode(), EpochModeDisabled);
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+
+        check!(vault.pause_state().allows_deposits(), VaultPaused);
+        check!(!vault.is_winding_down(), VaultWindingDown);
+        check!(token_info.is_enabled(), DepositsDisabled);
+        check!(
+            self.epoch_ticket.can_queue(vault.epoch),
+            EpochTicketUnclaimed
+        );
+
+        // everything queued in this epoch lands in the [`CypherSubAccount`] once it is rolled
+        let queued_amount = vault.pending_deposits.saturating_add(deposit_amount);
+        check!(
+            token_info.deposits.saturating_add(queued_amount) <= token_info.deposit_limit,
+            DepositLimitExceeded
+        );
+
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        let queued_value = Vault::get_quote_value(token_info, queued_amount, &cache_account);
+        check!(
+            !vault.exceeds_quote_deposit_limit(queued_value, &sub_accounts, &cache_account),
+            VaultDepositLimitExceeded
+        );
+
+        Ok(())
+    }
+
+    /// Transfer the deposit into the [`Vault`]'s epoch escrow.
+    pub fn invoke_transfer(&self, amount: u64) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.source_token_account.to_account_info(),
+            to: self.escrow_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        transfer(CpiContext::new(cpi_program, cpi_accounts), amount)
+    }
+}
+
+/// The user wants to deposit a token amount represented by `deposit_amount` into an epoch [`Vault`],
+/// the deposit is held in escrow until the epoch is rolled and the LP tokens can then be claimed.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, QueueDeposit<'info>>,
+    deposit_amount: u64,
+) -> Result<()> {
+    ctx.accounts
+        .validate(ctx.remaining_accounts, deposit_amount)?;
+
+    ctx.accounts.invoke_transfer(deposit_amount)?;
+
+    let ticket_bump = *ctx.bumps.get("epoch_ticket").unwrap();
+    let vault = ctx.accounts.vault.key();
+    let owner = ctx.accounts.authority.key();
+
+    let is_new_ticket = ctx.accounts.epoch_ticket.owner == Pubkey::default();
+    let epoch = {
+        let mut vault = ctx.accounts.vault.load_mut()?;
+        vault.pending_deposits = vault
+            .pending_deposits
+            .checked_add(deposit_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if is_new_ticket {
+            vault.epoch_ticket_count = vault
+                .epoch_ticket_count
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        vault.epoch
+    };
+
+    let epoch_ticket = &mut ctx.accounts.epoch_ticket;
+    if is_new_ticket {
+        epoch_ticket.init(vault, owner, ticket_bump);
+    }
+    epoch_ticket.queue_deposit(epoch, deposit_amount)?;
+
+    emit!(DepositQueued {
+        vault,
+        owner,
+        epoch,
+        amount: deposit_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
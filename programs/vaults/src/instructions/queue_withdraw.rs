@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::WithdrawQueued,
+    state::{
+        DepositorPosition, EpochTicket, Vault, DEPOSITOR_POSITION_SEED, EPOCH_LP_ESCROW_SEED,
+        EPOCH_TICKET_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct QueueWithdraw<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = authority,
+    )]
+    pub lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            EPOCH_TICKET_SEED,
+            vault.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = EpochTicket::LEN,
+    )]
+    pub epoch_ticket: Box<Account<'info, EpochTicket>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            EPOCH_LP_ESCROW_SEED,
+            vault.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            DEPOSITOR_POSITION_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = DepositorPosition::LEN,
+    )]
+    pub depositor_position: Box<Account<'info, DepositorPosition>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> QueueWithdraw<'info> {
+    /// We need to validate that this is an epoch [`Vault`].
+    pub fn validate(&self, lp_amount: u64) -> Result<()> {
+        check!(lp_amount != 0, ZeroAmount);
+        let vault = self.vault.load()?;
+        check!(vault.is_epoch_mode(), EpochModeDisabled);
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        check!(self.lp_mint.key() == token_info.lp_mint, InvalidLpMint);
+        check!(vault.pause_state().allows_withdrawals(), VaultPaused);
+        // the LPs of a winding down vault exit through `claim_wind_down` instead
+        check!(!vault.is_winding_down(), VaultWindingDown);
+        check!(
+            self.epoch_ticket.can_queue(vault.epoch),
+            EpochTicketUnclaimed
+        );
+        Ok(())
+    }
+
+    /// Lock the LP tokens in the [`Vault`]'s epoch escrow.
+    pub fn invoke_transfer(&self, amount: u64) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.lp_token_account.to_account_info(),
+            to: self.escrow_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        transfer(CpiContext::new(cpi_program, cpi_accounts), amount)
+    }
+}
+
+/// The user wants to redeem an amount of LP tokens represented by `lp_amount` from an epoch [`Vault`],
+/// these are locked in escrow until the epoch is rolled and the SPL Tokens can then be claimed.
+pub fn handler(ctx: Context<QueueWithdraw>, lp_amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.validate(lp_amount)?;
+
+    ctx.accounts.invoke_transfer(lp_amount)?;
+
+    let ticket_bump = *ctx.bumps.get("epoch_ticket").unwrap();
+    let position_bump = *ctx.bumps.get("depositor_position").unwrap();
+
+    let vault = ctx.accounts.vault.key();
+    let owner = ctx.accounts.authority.key();
+    let token_mint = ctx.accounts.token_mint.key();
+
+    let is_new_ticket = ctx.accounts.epoch_ticket.owner == Pubkey::default();
+    let epoch = {
+        let mut vault = ctx.accounts.vault.load_mut()?;
+        vault.pending_withdrawals = vault
+            .pending_withdrawals
+            .checked_add(lp_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if is_new_ticket {
+            vault.epoch_ticket_count = vault
+                .epoch_ticket_count
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        vault.epoch
+    };
+
+    let depositor_position = &mut ctx.accounts.depositor_position;
+    if depositor_position.owner == Pubkey::default() {
+        depositor_position.init(vault, token_mint, owner, position_bump);
+    }
+
+    let epoch_ticket = &mut ctx.accounts.epoch_ticket;
+    if is_new_ticket {
+        epoch_ticket.init(vault, owner, ticket_bump);
+    }
+    epoch_ticket.queue_withdraw(epoch, lp_amount)?;
+
+    emit!(WithdrawQueued {
+        vault,
+        owner,
+        epoch,
+        lp_amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    check,
+    events::{FeesAccrued, Withdrawn},
+    Withdraw,
+};
+
+/// The user wants to redeem an exact amount of LP tokens represented by `lp_amount`,
+/// taking this number we need to calculate how many tokens we are going to withdraw for the user.
+///
+/// The redemption fails if fewer than `min_tokens_out` tokens would be withdrawn.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+    lp_amount: u64,
+    min_tokens_out: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.validate()?;
+
+    // value the [`Vault`]'s holdings before the withdrawal leaves the [`CypherSubAccount`]
+    let net_assets = ctx.accounts.get_share_net_assets(ctx.remaining_accounts)?;
+
+    // accrue the fees up until now so the redeemer pays their share of them
+    let token_mint = ctx.accounts.token_mint.key();
+    let fee_amount = ctx
+        .accounts
+        .vault
+        .load_mut()?
+        .accrue_fees(token_mint, net_assets, now)?;
+    if fee_amount != 0 {
+        ctx.accounts.invoke_mint_fees(fee_amount)?;
+    }
+    let share_price = ctx
+        .accounts
+        .vault
+        .load()?
+        .get_share_price(token_mint, net_assets)?;
+    emit!(FeesAccrued {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        fee_recipient: ctx.accounts.vault.load()?.fee_recipient,
+        lp_minted: fee_amount,
+        share_price,
+        timestamp: now,
+    });
+
+    let share_amount: u64 = ctx
+        .accounts
+        .vault
+        .load()?
+        .get_share_info(token_mint)
+        .unwrap()
+        .calculate_redeem_amount(lp_amount, net_assets)?;
+    let withdraw_amount = ctx.accounts.from_share_units(share_amount)?;
+    check!(withdraw_amount != 0, ZeroAmount);
+    check!(withdraw_amount >= min_tokens_out, SlippageExceeded);
+
+    // burn exactly the given amount
+    ctx.accounts.invoke_burn(lp_amount)?;
+
+    // finally withdraw from the [`Vault`]'s [`CypherAccount`], spread across its sub accounts
+    for (sub_account, amount) in ctx
+        .accounts
+        .get_withdraw_amounts(ctx.remaining_accounts, withdraw_amount)?
+    {
+        ctx.accounts.invoke_withdraw_funds(sub_account, amount)?;
+    }
+
+    // update the [`Vault`]'s data
+    ctx.accounts
+        .vault
+        .load_mut()?
+        .record_withdraw(token_mint, withdraw_amount, lp_amount)?;
+
+    // record the withdrawal in the depositor's position
+    let position_bump = *ctx.bumps.get("depositor_position").unwrap();
+    ctx.accounts
+        .record_position_withdraw(position_bump, withdraw_amount, lp_amount);
+
+    emit!(Withdrawn {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        owner: ctx.accounts.authority.key(),
+        amount: withdraw_amount,
+        lp_burned: lp_amount,
+        share_price,
+        timestamp: now,
+    });
+
+    Ok(())
+}
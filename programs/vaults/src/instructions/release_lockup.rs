@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::LockupReleased,
+    state::{DepositorPosition, Vault, DEPOSITOR_POSITION_SEED, LOCKUP_ESCROW_SEED, VAULT_SEED},
+};
+
+#[derive(Accounts)]
+pub struct ReleaseLockup<'info> {
+    #[account(
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner,
+    )]
+    pub lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [
+            DEPOSITOR_POSITION_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = depositor_position.bump,
+    )]
+    pub depositor_position: Box<Account<'info, DepositorPosition>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LOCKUP_ESCROW_SEED,
+            depositor_position.key().as_ref(),
+        ],
+        bump,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub lockup_escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ReleaseLockup<'info> {
+    /// We need to validate that we have the correct SPL Token and that the lockup period has passed.
+    pub fn validate(&self, now: i64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let share_info = vault.get_share_info(self.token_mint.key()).unwrap();
+        check!(self.lp_mint.key() == share_info.lp_mint, InvalidLpMint);
+        // the LPs of a winding down vault must be able to claim their share
+        check!(
+            vault.is_winding_down()
+                || !self
+                    .depositor_position
+                    .is_locked(token_info.lockup_period, now),
+            DepositLocked
+        );
+        Ok(())
+    }
+
+    /// Release the LP tokens from the [`DepositorPosition`]'s lockup escrow.
+    pub fn invoke_transfer(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.lockup_escrow_token_account.to_account_info(),
+            to: self.lp_token_account.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        transfer(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+}
+
+/// The owner releases the LP tokens received for their deposits once the lockup period since their last deposit has passed.
+///
+/// The LP tokens are held in an escrow of the [`DepositorPosition`] instead of the owner's token account,
+/// so they can not be moved to another wallet and redeemed from a fresh position while they are locked.
+pub fn handler(ctx: Context<ReleaseLockup>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.validate(now)?;
+
+    let lp_amount = ctx.accounts.lockup_escrow_token_account.amount;
+    ctx.accounts.invoke_transfer(lp_amount)?;
+
+    emit!(LockupReleased {
+        vault: ctx.accounts.vault.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        owner: ctx.accounts.owner.key(),
+        lp_amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
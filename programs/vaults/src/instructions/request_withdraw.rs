@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::WithdrawRequested,
+    state::{
+        DepositorPosition, Vault, WithdrawTicket, DEPOSITOR_POSITION_SEED, WITHDRAW_ESCROW_SEED,
+        WITHDRAW_TICKET_SEED,
+    },
+};
+
+#[derive(Accounts)]
+pub struct RequestWithdraw<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = authority,
+    )]
+    pub lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            DEPOSITOR_POSITION_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = DepositorPosition::LEN,
+    )]
+    pub depositor_position: Box<Account<'info, DepositorPosition>>,
+
+    #[account(
+        init,
+        seeds = [
+            WITHDRAW_TICKET_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = WithdrawTicket::LEN,
+    )]
+    pub withdraw_ticket: Box<Account<'info, WithdrawTicket>>,
+
+    #[account(
+        init,
+        seeds = [
+            WITHDRAW_ESCROW_SEED,
+            withdraw_ticket.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> RequestWithdraw<'info> {
+    /// We need to validate that we have the correct SPL Token.
+    pub fn validate(&self, lp_amount: u64) -> Result<()> {
+        check!(lp_amount != 0, ZeroAmount);
+        let vault = self.vault.load()?;
+        vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let share_info = vault.get_share_info(self.token_mint.key()).unwrap();
+        check!(self.lp_mint.key() == share_info.lp_mint, InvalidLpMint);
+        check!(vault.pause_state().allows_withdrawals(), VaultPaused);
+        check!(!vault.is_epoch_mode(), EpochModeEnabled);
+        Ok(())
+    }
+
+    /// Lock the LP tokens in the [`WithdrawTicket`]'s escrow.
+    pub fn invoke_transfer(&self, amount: u64) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.lp_token_account.to_account_info(),
+            to: self.escrow_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        transfer(CpiContext::new(cpi_program, cpi_accounts), amount)
+    }
+}
+
+/// The user wants to redeem an amount of LP tokens represented by `lp_amount`,
+/// these are locked in escrow until the [`WithdrawTicket`] is settled by `process_withdraw`.
+///
+/// The [`WithdrawTicket`] can only be settled for at least `min_tokens_out` tokens.
+pub fn handler(ctx: Context<RequestWithdraw>, lp_amount: u64, min_tokens_out: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.validate(lp_amount)?;
+
+    ctx.accounts.invoke_transfer(lp_amount)?;
+
+    let ticket_bump = *ctx.bumps.get("withdraw_ticket").unwrap();
+    let position_bump = *ctx.bumps.get("depositor_position").unwrap();
+
+    let vault = ctx.accounts.vault.key();
+    let owner = ctx.accounts.authority.key();
+    let token_mint = ctx.accounts.token_mint.key();
+
+    let depositor_position = &mut ctx.accounts.depositor_position;
+    if depositor_position.owner == Pubkey::default() {
+        depositor_position.init(vault, token_mint, owner, position_bump);
+    }
+
+    {
+        let mut vault = ctx.accounts.vault.load_mut()?;
+        vault.withdraw_ticket_count = vault
+            .withdraw_ticket_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    let withdraw_ticket = &mut ctx.accounts.withdraw_ticket;
+    withdraw_ticket.init(
+        vault,
+        owner,
+        token_mint,
+        lp_amount,
+        min_tokens_out,
+        ticket_bump,
+        now,
+    );
+
+    emit!(WithdrawRequested {
+        vault,
+        token_mint,
+        owner,
+        withdraw_ticket: withdraw_ticket.key(),
+        lp_amount,
+        min_tokens_out,
+        timestamp: now,
+    });
+
+    Ok(())
+}
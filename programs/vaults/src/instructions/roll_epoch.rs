@@ -0,0 +1,421 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, mint_to, Burn, Mint, MintTo, Token, TokenAccount};
+use cypher_client::{
+    cpi::{
+        accounts::{DepositFunds, WithdrawFunds},
+        deposit_funds, withdraw_funds,
+    },
+    program::Cypher,
+    CacheAccount, Clearing, CypherAccount, CypherSubAccount, Pool, PoolNode,
+};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::{EpochRolled, FeesAccrued},
+    state::{
+        EpochRecord, Vault, EPOCH_LP_ESCROW_SEED, EPOCH_RECORD_SEED, EPOCH_TOKEN_ESCROW_SEED,
+        VAULT_SEED,
+    },
+};
+
+/// The [`Vault`]'s strategy sub accounts are passed as writable remaining accounts, see [`Vault::load_sub_accounts`].
+#[derive(Accounts)]
+pub struct RollEpoch<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    #[account(mut)]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = vault.load()?.fee_recipient,
+    )]
+    pub fee_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        seeds = [
+            EPOCH_RECORD_SEED,
+            vault.key().as_ref(),
+            vault.load()?.epoch.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = keeper,
+        space = EpochRecord::LEN,
+    )]
+    pub epoch_record: Box<Account<'info, EpochRecord>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            EPOCH_TOKEN_ESCROW_SEED,
+            vault.key().as_ref(),
+        ],
+        bump,
+        payer = keeper,
+        token::mint = token_mint,
+        token::authority = vault,
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            EPOCH_LP_ESCROW_SEED,
+            vault.key().as_ref(),
+        ],
+        bump,
+        payer = keeper,
+        token::mint = lp_mint,
+        token::authority = vault,
+    )]
+    pub escrow_lp_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    #[account(mut)]
+    pub cypher_account: AccountLoader<'info, CypherAccount>,
+
+    #[account(mut)]
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(mut)]
+    pub pool_node: AccountLoader<'info, PoolNode>,
+
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Checked via CPI to [`Cypher`].
+    pub vault_signer: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub cypher_program: Program<'info, Cypher>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> RollEpoch<'info> {
+    /// We need to validate that this is an epoch [`Vault`] whose current epoch has ended.
+    pub fn validate(&self, now: i64) -> Result<()> {
+        let vault = self.vault.load()?;
+        check!(vault.is_epoch_mode(), EpochModeDisabled);
+        let token_info = vault
+            .get_token_info(self.token_mint.key())
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        check!(self.lp_mint.key() == token_info.lp_mint, InvalidLpMint);
+        // queued deposits can only be let in while deposits are allowed, queued withdrawals whenever withdrawals are,
+        // while the [`Vault`] only allows withdrawals the queued deposits can still be taken back with `cancel_epoch_ticket`
+        check!(
+            vault.pause_state().allows_deposits()
+                || (vault.pause_state().allows_withdrawals() && vault.pending_deposits == 0),
+            VaultPaused
+        );
+        // once the [`Vault`] winds down the flows still queued can only be taken back with `cancel_epoch_ticket`
+        check!(!vault.is_winding_down(), VaultWindingDown);
+        check!(vault.is_epoch_over(now), EpochNotOver);
+        Ok(())
+    }
+
+    /// Gets the marked to market value backing the LP token of the [`Vault`]'s SPL Token.
+    pub fn get_share_net_assets(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let vault = self.vault.load()?;
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        vault.get_share_net_assets(&self.token_mint.key(), &sub_accounts, &cache_account)
+    }
+
+    /// Splits a withdrawal across the [`Vault`]'s [`CypherSubAccount`]s, see [`Vault::get_withdraw_amounts`].
+    pub fn get_withdraw_amounts(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        amount: u64,
+    ) -> Result<Vec<(AccountInfo<'info>, u64)>> {
+        let vault = self.vault.load()?;
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        let withdraw_amounts = Vault::get_withdraw_amounts(
+            &self.token_mint.key(),
+            amount,
+            &sub_accounts,
+            &cache_account,
+        );
+        Ok(sub_account_loaders
+            .iter()
+            .map(|sa| sa.to_account_info())
+            .zip(withdraw_amounts)
+            .filter(|(_, amount)| *amount != 0)
+            .collect())
+    }
+
+    /// Deposit the input amount from the epoch escrow to the [`cypher_client::CypherAccount`].
+    pub fn invoke_deposit_funds(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.cypher_program.to_account_info();
+        let cpi_accounts = DepositFunds {
+            clearing: self.clearing.to_account_info(),
+            cache_account: self.cache_account.to_account_info(),
+            master_account: self.cypher_account.to_account_info(),
+            sub_account: self.cypher_sub_account.to_account_info(),
+            pool: self.pool.to_account_info(),
+            pool_node: self.pool_node.to_account_info(),
+            token_vault: self.token_vault.to_account_info(),
+            source_token_account: self.escrow_token_account.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            authority: self.vault.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        deposit_funds(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+
+    /// Withdraw the input amount from the [`cypher_client::CypherAccount`] to the epoch escrow.
+    pub fn invoke_withdraw_funds(
+        &self,
+        sub_account: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.cypher_program.to_account_info();
+        let cpi_accounts = WithdrawFunds {
+            clearing: self.clearing.to_account_info(),
+            cache_account: self.cache_account.to_account_info(),
+            master_account: self.cypher_account.to_account_info(),
+            sub_account,
+            pool: self.pool.to_account_info(),
+            pool_node: self.pool_node.to_account_info(),
+            token_vault: self.token_vault.to_account_info(),
+            destination_token_account: self.escrow_token_account.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            vault_signer: self.vault_signer.to_account_info(),
+            authority: self.vault.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        withdraw_funds(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+
+    /// Mint the given amount of LP tokens to the destination token account.
+    pub fn invoke_mint_to(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = MintTo {
+            mint: self.lp_mint.to_account_info(),
+            to,
+            authority: self.vault.to_account_info(),
+        };
+        mint_to(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+
+    /// Burn the LP tokens queued for withdrawal in the epoch escrow.
+    pub fn invoke_burn(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Burn {
+            mint: self.lp_mint.to_account_info(),
+            from: self.escrow_lp_token_account.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        burn(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+}
+
+/// The keeper wants to roll an epoch [`Vault`] into its next epoch once the current one has ended.
+///
+/// All deposits and withdrawals queued during the epoch are processed at the same share price,
+/// only the difference between them moves in or out of the [`CypherSubAccount`]. The minted LP tokens
+/// and withdrawn SPL Tokens stay in the epoch escrows until they are claimed with `claim_epoch`.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, RollEpoch<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.validate(now)?;
+
+    // value the [`Vault`]'s holdings before any of the queued flows are processed
+    let net_assets = ctx.accounts.get_share_net_assets(ctx.remaining_accounts)?;
+
+    // accrue the fees up until now so the queued flows are processed at the share price net of them
+    let token_mint = ctx.accounts.token_mint.key();
+    let fee_amount = ctx
+        .accounts
+        .vault
+        .load_mut()?
+        .accrue_fees(token_mint, net_assets, now)?;
+    if fee_amount != 0 {
+        let fee_token_account = ctx.accounts.fee_token_account.to_account_info();
+        ctx.accounts.invoke_mint_to(fee_token_account, fee_amount)?;
+    }
+    let share_price = ctx
+        .accounts
+        .vault
+        .load()?
+        .get_share_price(token_mint, net_assets)?;
+    emit!(FeesAccrued {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        fee_recipient: ctx.accounts.vault.load()?.fee_recipient,
+        lp_minted: fee_amount,
+        share_price,
+        timestamp: now,
+    });
+
+    let (epoch, deposits, burn_amount) = {
+        let vault = ctx.accounts.vault.load()?;
+        (
+            vault.epoch,
+            vault.pending_deposits,
+            vault.pending_withdrawals,
+        )
+    };
+
+    // process the queued withdrawals first, burning the escrowed LP tokens
+    let withdraw_amount: u64 = ctx
+        .accounts
+        .vault
+        .load()?
+        .get_share_info(token_mint)
+        .unwrap()
+        .calculate_redeem_amount(burn_amount, net_assets)?;
+    if burn_amount != 0 {
+        ctx.accounts.invoke_burn(burn_amount)?;
+    }
+    ctx.accounts
+        .vault
+        .load_mut()?
+        .record_withdraw(token_mint, withdraw_amount, burn_amount)?;
+
+    // then the queued deposits, at the same share price
+    let mint_amount: u64 = ctx
+        .accounts
+        .vault
+        .load()?
+        .get_share_info(token_mint)
+        .unwrap()
+        .calculate_mint_amount(deposits, net_assets.saturating_sub(withdraw_amount))?;
+    if mint_amount != 0 {
+        let escrow_lp_token_account = ctx.accounts.escrow_lp_token_account.to_account_info();
+        ctx.accounts
+            .invoke_mint_to(escrow_lp_token_account, mint_amount)?;
+    }
+    ctx.accounts
+        .vault
+        .load_mut()?
+        .record_deposit(token_mint, deposits, deposits, mint_amount)?;
+
+    // only the net flow moves between the epoch escrow and the [`Vault`]'s [`CypherAccount`]
+    if deposits > withdraw_amount {
+        ctx.accounts
+            .invoke_deposit_funds(deposits - withdraw_amount)?;
+    } else if withdraw_amount > deposits {
+        for (sub_account, amount) in ctx
+            .accounts
+            .get_withdraw_amounts(ctx.remaining_accounts, withdraw_amount - deposits)?
+        {
+            ctx.accounts.invoke_withdraw_funds(sub_account, amount)?;
+        }
+    }
+
+    let epoch_record = &mut ctx.accounts.epoch_record;
+    epoch_record.vault = ctx.accounts.vault.key();
+    epoch_record.epoch = epoch;
+    epoch_record.deposits = deposits;
+    epoch_record.lp_minted = mint_amount;
+    epoch_record.lp_burned = burn_amount;
+    epoch_record.withdrawn = withdraw_amount;
+    epoch_record.share_price = share_price;
+    epoch_record.rolled_at = now;
+
+    ctx.accounts.vault.load_mut()?.start_next_epoch(now)?;
+
+    emit!(EpochRolled {
+        vault: ctx.accounts.vault.key(),
+        epoch,
+        deposits,
+        lp_minted: mint_amount,
+        lp_burned: burn_amount,
+        withdrawn: withdraw_amount,
+        share_price,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -1,14 +1,16 @@
 use anchor_lang::prelude::*;
 
-use crate::Vault;
+use crate::{error::ErrorCode, DepositLimitSet, Vault};
 
 #[derive(Accounts)]
 pub struct SetDepositLimit<'info> {
     #[account(
         mut,
-        has_one = authority,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
     )]
-    pub vault: Box<Account<'info, Vault>>,
+    pub vault: AccountLoader<'info, Vault>,
 
     pub authority: Signer<'info>,
 }
@@ -16,7 +18,8 @@ pub struct SetDepositLimit<'info> {
 impl<'info> SetDepositLimit<'info> {
     /// Enables deposits for the given SPL Token Mint.
     fn set_deposit_limit(&mut self, token_mint: Pubkey, deposit_limit: u64) -> Result<()> {
-        let token_info = self.vault.get_token_info_mut(token_mint).unwrap();
+        let mut vault = self.vault.load_mut()?;
+        let token_info = vault.get_token_info_mut(token_mint).unwrap();
         token_info.deposit_limit = deposit_limit;
         Ok(())
     }
@@ -28,5 +31,13 @@ pub fn handler(
     deposit_limit: u64,
 ) -> Result<()> {
     ctx.accounts.set_deposit_limit(token_mint, deposit_limit)?;
+
+    emit!(DepositLimitSet {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        deposit_limit,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
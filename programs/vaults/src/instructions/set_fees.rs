@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{check, error::ErrorCode, FeesSet, SetFeesArgs, Vault, BPS_DENOMINATOR};
+
+#[derive(Accounts)]
+pub struct SetFees<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> SetFees<'info> {
+    /// We need to validate that the fees do not exceed 100%.
+    pub fn validate(&self, args: &SetFeesArgs) -> Result<()> {
+        check!(
+            args.management_fee_bps as u128 <= BPS_DENOMINATOR,
+            InvalidFee
+        );
+        check!(
+            args.performance_fee_bps as u128 <= BPS_DENOMINATOR,
+            InvalidFee
+        );
+        Ok(())
+    }
+}
+
+/// The fees of every LP token are expected to be accrued in the same transaction beforehand,
+/// see [`crate::instructions::accrue_fees`].
+pub fn handler(ctx: Context<SetFees>, args: SetFeesArgs) -> Result<()> {
+    ctx.accounts.validate(&args)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.vault.load_mut()?.set_fees(&args, now)?;
+
+    emit!(FeesSet {
+        vault: ctx.accounts.vault.key(),
+        fee_recipient: args.fee_recipient,
+        management_fee_bps: args.management_fee_bps,
+        performance_fee_bps: args.performance_fee_bps,
+        timestamp: now,
+    });
+
+    Ok(())
+}
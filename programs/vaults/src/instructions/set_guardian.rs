@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, GuardianSet, Vault};
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> SetGuardian<'info> {
+    /// Sets the guardian of the [`Vault`].
+    fn set_guardian(&mut self, guardian: Pubkey) -> Result<()> {
+        self.vault.load_mut()?.guardian = guardian;
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    ctx.accounts.set_guardian(guardian)?;
+
+    emit!(GuardianSet {
+        vault: ctx.accounts.vault.key(),
+        guardian,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
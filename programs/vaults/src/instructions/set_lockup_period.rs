@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{check, error::ErrorCode, LockupPeriodSet, Vault};
+
+#[derive(Accounts)]
+pub struct SetLockupPeriod<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> SetLockupPeriod<'info> {
+    /// We need to validate that the lockup period is not negative.
+    pub fn validate(&self, lockup_period: i64) -> Result<()> {
+        check!(lockup_period >= 0, InvalidLockupPeriod);
+        Ok(())
+    }
+
+    /// Sets the lockup period for deposits of the given SPL Token Mint.
+    fn set_lockup_period(&mut self, token_mint: Pubkey, lockup_period: i64) -> Result<()> {
+        let mut vault = self.vault.load_mut()?;
+        let token_info = vault
+            .get_token_info_mut(token_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        token_info.lockup_period = lockup_period;
+        Ok(())
+    }
+}
+
+pub fn handler(
+    ctx: Context<SetLockupPeriod>,
+    token_mint: Pubkey,
+    lockup_period: i64,
+) -> Result<()> {
+    ctx.accounts.validate(lockup_period)?;
+
+    ctx.accounts.set_lockup_period(token_mint, lockup_period)?;
+
+    emit!(LockupPeriodSet {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        lockup_period,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
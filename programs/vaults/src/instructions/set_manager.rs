@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, ManagerSet, Vault};
+
+#[derive(Accounts)]
+pub struct SetManager<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> SetManager<'info> {
+    /// Sets the manager of the [`Vault`].
+    fn set_manager(&mut self, manager: Pubkey) -> Result<()> {
+        self.vault.load_mut()?.manager = manager;
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<SetManager>, manager: Pubkey) -> Result<()> {
+    ctx.accounts.set_manager(manager)?;
+
+    emit!(ManagerSet {
+        vault: ctx.accounts.vault.key(),
+        manager,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{check, error::ErrorCode, MaxWithdrawWaitSet, Vault};
+
+#[derive(Accounts)]
+pub struct SetMaxWithdrawWait<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> SetMaxWithdrawWait<'info> {
+    /// We need to validate that the maximum wait is not negative.
+    pub fn validate(&self, max_withdraw_wait: i64) -> Result<()> {
+        check!(max_withdraw_wait >= 0, InvalidMaxWithdrawWait);
+        Ok(())
+    }
+
+    /// Sets the maximum time a [`crate::WithdrawTicket`] can wait before anyone can settle it.
+    fn set_max_withdraw_wait(&mut self, max_withdraw_wait: i64) -> Result<()> {
+        self.vault.load_mut()?.max_withdraw_wait = max_withdraw_wait;
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<SetMaxWithdrawWait>, max_withdraw_wait: i64) -> Result<()> {
+    ctx.accounts.validate(max_withdraw_wait)?;
+
+    ctx.accounts.set_max_withdraw_wait(max_withdraw_wait)?;
+
+    emit!(MaxWithdrawWaitSet {
+        vault: ctx.accounts.vault.key(),
+        max_withdraw_wait,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
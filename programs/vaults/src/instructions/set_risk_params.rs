@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use cypher_client::Clearing;
+
+use crate::{error::ErrorCode, RiskParams, RiskParamsSet, Vault};
+
+#[derive(Accounts)]
+pub struct SetRiskParams<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> SetRiskParams<'info> {
+    /// We need to validate that the minimum margin ratio is not below Cypher's maintenance margin.
+    pub fn validate(&self, risk_params: &RiskParams) -> Result<()> {
+        risk_params.validate(&self.clearing.load()?)
+    }
+
+    /// Sets the risk limits of the [`Vault`]'s sub account with the given number.
+    fn set_risk_params(&mut self, sub_account_number: u8, risk_params: RiskParams) -> Result<()> {
+        let mut vault = self.vault.load_mut()?;
+        *vault
+            .get_risk_params_mut(sub_account_number)
+            .ok_or(ErrorCode::InvalidCypherAccount)? = risk_params;
+        Ok(())
+    }
+}
+
+/// The authority sets the risk limits of one of the [`Vault`]'s sub accounts, the primary or a strategy sub account.
+///
+/// Only the markets in the allowlist can be traded, see [`RiskParams::allowed_markets`].
+pub fn handler(
+    ctx: Context<SetRiskParams>,
+    sub_account_number: u8,
+    risk_params: RiskParams,
+) -> Result<()> {
+    ctx.accounts.validate(&risk_params)?;
+
+    ctx.accounts
+        .set_risk_params(sub_account_number, risk_params)?;
+
+    emit!(RiskParamsSet {
+        vault: ctx.accounts.vault.key(),
+        sub_account_number,
+        risk_params,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
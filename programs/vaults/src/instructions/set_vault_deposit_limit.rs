@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, Vault, VaultDepositLimitSet};
+
+#[derive(Accounts)]
+pub struct SetVaultDepositLimit<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+        constraint = vault.load()?.allows_direct_config_changes() @ ErrorCode::ConfigChangeTimelocked,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> SetVaultDepositLimit<'info> {
+    /// Sets the vault-wide deposit limit, in native units of the quote token.
+    fn set_vault_deposit_limit(&mut self, quote_deposit_limit: u64) -> Result<()> {
+        self.vault.load_mut()?.quote_deposit_limit = quote_deposit_limit;
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<SetVaultDepositLimit>, quote_deposit_limit: u64) -> Result<()> {
+    ctx.accounts.set_vault_deposit_limit(quote_deposit_limit)?;
+
+    emit!(VaultDepositLimitSet {
+        vault: ctx.accounts.vault.key(),
+        quote_deposit_limit,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::{MarketType, Trade, TradeOperation};
+
+/// Settles the funds of filled orders on a Cypher market, signed by the [`crate::Vault`].
+///
+/// The market's accounts are passed as remaining accounts, see [`crate::DerivativeMarketAccounts`] and [`crate::SpotMarketAccounts`].
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+    market_type: MarketType,
+) -> Result<()> {
+    ctx.accounts.validate(TradeOperation::SettleFunds)?;
+
+    ctx.accounts
+        .invoke_settle_funds(ctx.remaining_accounts, market_type)?;
+    ctx.accounts
+        .emit_trade_executed(market_type, TradeOperation::SettleFunds)?;
+
+    // this can only reduce the [`crate::Vault`]'s risk, so it is not held to the risk limits
+    Ok(())
+}
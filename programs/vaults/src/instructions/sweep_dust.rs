@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use cypher_client::{
+    cpi::{accounts::WithdrawFunds, withdraw_funds},
+    program::Cypher,
+    utils::derive_account_address,
+    CacheAccount, Clearing, CypherAccount, CypherSubAccount, Pool, PoolNode,
+};
+
+use crate::{
+    check,
+    error::ErrorCode,
+    events::DustSwept,
+    state::{Vault, VAULT_SEED},
+};
+
+#[derive(Accounts)]
+pub struct SweepDust<'info> {
+    #[account(
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    #[account(mut)]
+    pub cypher_account: AccountLoader<'info, CypherAccount>,
+
+    /// Any of the [`Vault`]'s sub accounts, the primary or a strategy sub account.
+    #[account(mut)]
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    #[account(mut)]
+    pub pool_node: AccountLoader<'info, PoolNode>,
+
+    #[account(mut)]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = authority,
+    )]
+    pub destination_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Checked via CPI to [`Cypher`].
+    pub vault_signer: AccountInfo<'info>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub cypher_program: Program<'info, Cypher>,
+}
+
+impl<'info> SweepDust<'info> {
+    /// We need to validate that nothing is owed to any depositor anymore and that the Cypher accounts are the [`Vault`]'s.
+    ///
+    /// Every LP token has to be burned and every queued flow and ticket settled, see [`Vault::has_unsettled_flows`].
+    pub fn validate(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        check!(!vault.has_lp_supply(), TokenWithLpSupply);
+        check!(!vault.has_unsettled_flows(), UnsettledFlows);
+
+        let (cypher_account, _) = derive_account_address(&self.vault.key(), vault.account_number);
+        check!(
+            self.cypher_account.key() == cypher_account,
+            InvalidCypherAccount
+        );
+        check!(
+            vault
+                .get_risk_params(&self.vault.key(), &self.cypher_sub_account.key())
+                .is_some(),
+            InvalidCypherAccount
+        );
+        Ok(())
+    }
+
+    /// Gets the balance of the SPL Token left in the [`CypherSubAccount`].
+    pub fn get_spot_balance(&self) -> Result<u64> {
+        let sub_account = self.cypher_sub_account.load()?;
+        let cache_account = self.cache_account.load()?;
+        Ok(Vault::get_spot_balance(
+            &self.token_mint.key(),
+            &sub_account,
+            &cache_account,
+        ))
+    }
+
+    /// Withdraw the input amount from the [`cypher_client::CypherAccount`] to the authority.
+    pub fn invoke_withdraw_funds(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.cypher_program.to_account_info();
+        let cpi_accounts = WithdrawFunds {
+            clearing: self.clearing.to_account_info(),
+            cache_account: self.cache_account.to_account_info(),
+            master_account: self.cypher_account.to_account_info(),
+            sub_account: self.cypher_sub_account.to_account_info(),
+            pool: self.pool.to_account_info(),
+            pool_node: self.pool_node.to_account_info(),
+            token_vault: self.token_vault.to_account_info(),
+            destination_token_account: self.destination_token_account.to_account_info(),
+            token_mint: self.token_mint.to_account_info(),
+            vault_signer: self.vault_signer.to_account_info(),
+            authority: self.vault.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        withdraw_funds(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+}
+
+/// The authority sweeps the dust of an SPL Token left in one of the [`Vault`]'s sub accounts,
+/// so the Cypher accounts can be closed with `close_vault`.
+///
+/// Dust is whatever balance is left once every LP token has been burned and every queued flow and ticket settled,
+/// rounding in favour of the [`Vault`] leaves it behind and no depositor has a claim on it anymore.
+pub fn handler(ctx: Context<SweepDust>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let amount = ctx.accounts.get_spot_balance()?;
+    check!(amount != 0, ZeroAmount);
+    ctx.accounts.invoke_withdraw_funds(amount)?;
+
+    emit!(DustSwept {
+        vault: ctx.accounts.vault.key(),
+        sub_account: ctx.accounts.cypher_sub_account.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
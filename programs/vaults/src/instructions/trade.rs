@@ -0,0 +1,690 @@
+use anchor_lang::{prelude::*, solana_program::account_info::next_account_info};
+use cypher_client::{
+    cpi::{
+        accounts::{
+            CancelFuturesOrder, CancelFuturesOrders, CancelPerpOrder, CancelPerpOrders,
+            CancelSpotOrder, CancelSpotOrders, NewFuturesOrder, NewPerpOrder, NewSpotOrder,
+            SettleFuturesFunds, SettlePerpFunds, SettleSpotFunds,
+        },
+        cancel_futures_order, cancel_futures_orders, cancel_perp_order, cancel_perp_orders,
+        cancel_spot_order, cancel_spot_orders, new_futures_order, new_perp_order, new_spot_order,
+        settle_futures_funds, settle_perp_funds, settle_spot_funds,
+    },
+    program::Cypher,
+    quote_mint,
+    utils::derive_account_address,
+    CacheAccount, CancelOrderArgs, Clearing, CypherAccount, CypherSubAccount,
+    MarginCollateralRatioType,
+};
+use fixed::types::I80F48;
+
+use crate::{
+    check, error::ErrorCode, MarketType, NewOrderArgs, RiskParams, TradeExecuted, TradeOperation,
+    Vault, BPS_DENOMINATOR, MAX_SETTLEMENT_SLIPPAGE_BPS, VAULT_SEED,
+};
+
+/// The accounts shared by every manager instruction that trades through the [`Vault`]'s Cypher accounts.
+///
+/// The `cypher_sub_account` can be any of the [`Vault`]'s sub accounts, the trade is checked against its own risk limits.
+///
+/// The accounts of the market being traded are passed as remaining accounts,
+/// see [`DerivativeMarketAccounts`] and [`SpotMarketAccounts`].
+#[derive(Accounts)]
+pub struct Trade<'info> {
+    #[account(
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.manager == manager.key()
+            || vault.load()?.is_wind_down_overdue(Clock::get()?.unix_timestamp)
+            @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    #[account(mut)]
+    pub cypher_account: AccountLoader<'info, CypherAccount>,
+
+    #[account(mut)]
+    pub cypher_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    /// The [`Vault`]'s manager, or anyone once its wind-down is overdue, see [`Vault::is_wind_down_overdue`].
+    pub manager: Signer<'info>,
+
+    pub cypher_program: Program<'info, Cypher>,
+}
+
+/// The accounts of a Cypher perpetual or fixed expiry futures market, passed as remaining accounts in this order.
+///
+/// These are checked by [`Cypher`] against the market and the [`CypherSubAccount`] being traded.
+pub struct DerivativeMarketAccounts<'info> {
+    pub market: AccountInfo<'info>,
+    pub open_orders: AccountInfo<'info>,
+    pub orderbook: AccountInfo<'info>,
+    pub event_queue: AccountInfo<'info>,
+    pub bids: AccountInfo<'info>,
+    pub asks: AccountInfo<'info>,
+    pub quote_pool_node: AccountInfo<'info>,
+}
+
+impl<'info> DerivativeMarketAccounts<'info> {
+    /// Takes the market's accounts from the remaining accounts.
+    pub fn try_from(remaining_accounts: &[AccountInfo<'info>]) -> Result<Self> {
+        let accounts = &mut remaining_accounts.iter();
+        Ok(Self {
+            market: next_account_info(accounts)?.clone(),
+            open_orders: next_account_info(accounts)?.clone(),
+            orderbook: next_account_info(accounts)?.clone(),
+            event_queue: next_account_info(accounts)?.clone(),
+            bids: next_account_info(accounts)?.clone(),
+            asks: next_account_info(accounts)?.clone(),
+            quote_pool_node: next_account_info(accounts)?.clone(),
+        })
+    }
+}
+
+/// The accounts of a Serum spot market traded through Cypher, passed as remaining accounts in this order.
+///
+/// These are checked by [`Cypher`] against the market and the [`CypherSubAccount`] being traded.
+pub struct SpotMarketAccounts<'info> {
+    pub asset_pool_node: AccountInfo<'info>,
+    pub quote_pool_node: AccountInfo<'info>,
+    pub asset_mint: AccountInfo<'info>,
+    pub asset_vault: AccountInfo<'info>,
+    pub quote_vault: AccountInfo<'info>,
+    pub vault_signer: AccountInfo<'info>,
+    pub market: AccountInfo<'info>,
+    pub open_orders: AccountInfo<'info>,
+    pub event_queue: AccountInfo<'info>,
+    pub request_queue: AccountInfo<'info>,
+    pub bids: AccountInfo<'info>,
+    pub asks: AccountInfo<'info>,
+    pub coin_vault: AccountInfo<'info>,
+    pub pc_vault: AccountInfo<'info>,
+    pub dex_vault_signer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub dex_program: AccountInfo<'info>,
+}
+
+impl<'info> SpotMarketAccounts<'info> {
+    /// Takes the market's accounts from the remaining accounts.
+    pub fn try_from(remaining_accounts: &[AccountInfo<'info>]) -> Result<Self> {
+        let accounts = &mut remaining_accounts.iter();
+        Ok(Self {
+            asset_pool_node: next_account_info(accounts)?.clone(),
+            quote_pool_node: next_account_info(accounts)?.clone(),
+            asset_mint: next_account_info(accounts)?.clone(),
+            asset_vault: next_account_info(accounts)?.clone(),
+            quote_vault: next_account_info(accounts)?.clone(),
+            vault_signer: next_account_info(accounts)?.clone(),
+            market: next_account_info(accounts)?.clone(),
+            open_orders: next_account_info(accounts)?.clone(),
+            event_queue: next_account_info(accounts)?.clone(),
+            request_queue: next_account_info(accounts)?.clone(),
+            bids: next_account_info(accounts)?.clone(),
+            asks: next_account_info(accounts)?.clone(),
+            coin_vault: next_account_info(accounts)?.clone(),
+            pc_vault: next_account_info(accounts)?.clone(),
+            dex_vault_signer: next_account_info(accounts)?.clone(),
+            token_program: next_account_info(accounts)?.clone(),
+            dex_program: next_account_info(accounts)?.clone(),
+        })
+    }
+}
+
+impl<'info> Trade<'info> {
+    /// We need to validate that trading is not paused and that the Cypher accounts are the [`Vault`]'s.
+    ///
+    /// Cancelling orders and settling funds is allowed while the [`Vault`] is paused,
+    /// so the manager can still de-risk it. New orders of a winding down [`Vault`] are checked
+    /// afterwards with [`Trade::check_positions_reduced`], which also lets anyone close
+    /// the positions of an overdue wind-down while it is paused.
+    pub fn validate(&self, operation: TradeOperation) -> Result<()> {
+        let vault = self.vault.load()?;
+        check!(
+            operation.is_risk_reducing()
+                || vault.pause_state().allows_trading()
+                || vault.is_wind_down_overdue(Clock::get()?.unix_timestamp),
+            VaultPaused
+        );
+
+        let (cypher_account, _) = derive_account_address(&self.vault.key(), vault.account_number);
+        check!(
+            self.cypher_account.key() == cypher_account,
+            InvalidCypherAccount
+        );
+        check!(
+            vault
+                .get_risk_params(&self.vault.key(), &self.cypher_sub_account.key())
+                .is_some(),
+            InvalidCypherAccount
+        );
+        Ok(())
+    }
+
+    /// Invokes [`Cypher`]'s new order instruction for the market type of the order, signed by the [`Vault`].
+    pub fn invoke_new_order(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        args: NewOrderArgs,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let vault_id = vault.id.to_le_bytes();
+        let signer: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            vault.creator.as_ref(),
+            vault_id.as_ref(),
+            &[vault.bump],
+        ]];
+        let cpi_program = self.cypher_program.to_account_info();
+
+        match args {
+            NewOrderArgs::Perpetual(args) => {
+                let market = DerivativeMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = NewPerpOrder {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    orderbook: market.orderbook,
+                    event_queue: market.event_queue,
+                    bids: market.bids,
+                    asks: market.asks,
+                    quote_pool_node: market.quote_pool_node,
+                    authority: self.vault.to_account_info(),
+                };
+                new_perp_order(
+                    CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+                    args,
+                )
+            }
+            NewOrderArgs::Futures(args) => {
+                let market = DerivativeMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = NewFuturesOrder {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    orderbook: market.orderbook,
+                    event_queue: market.event_queue,
+                    bids: market.bids,
+                    asks: market.asks,
+                    quote_pool_node: market.quote_pool_node,
+                    authority: self.vault.to_account_info(),
+                };
+                new_futures_order(
+                    CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+                    args,
+                )
+            }
+            NewOrderArgs::Spot(args) => {
+                let market = SpotMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = NewSpotOrder {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    asset_pool_node: market.asset_pool_node,
+                    quote_pool_node: market.quote_pool_node,
+                    asset_mint: market.asset_mint,
+                    asset_vault: market.asset_vault,
+                    quote_vault: market.quote_vault,
+                    vault_signer: market.vault_signer,
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    event_queue: market.event_queue,
+                    request_queue: market.request_queue,
+                    bids: market.bids,
+                    asks: market.asks,
+                    coin_vault: market.coin_vault,
+                    pc_vault: market.pc_vault,
+                    dex_vault_signer: market.dex_vault_signer,
+                    authority: self.vault.to_account_info(),
+                    token_program: market.token_program,
+                    dex_program: market.dex_program,
+                };
+                new_spot_order(
+                    CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+                    args,
+                )
+            }
+        }
+    }
+
+    /// Invokes [`Cypher`]'s cancel order instruction for the given market type, signed by the [`Vault`].
+    pub fn invoke_cancel_order(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        market_type: MarketType,
+        args: CancelOrderArgs,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let vault_id = vault.id.to_le_bytes();
+        let signer: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            vault.creator.as_ref(),
+            vault_id.as_ref(),
+            &[vault.bump],
+        ]];
+        let cpi_program = self.cypher_program.to_account_info();
+
+        match market_type {
+            MarketType::Perpetual => {
+                let market = DerivativeMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = CancelPerpOrder {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    orderbook: market.orderbook,
+                    event_queue: market.event_queue,
+                    bids: market.bids,
+                    asks: market.asks,
+                    quote_pool_node: market.quote_pool_node,
+                    authority: self.vault.to_account_info(),
+                };
+                cancel_perp_order(
+                    CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+                    args,
+                )
+            }
+            MarketType::Futures => {
+                let market = DerivativeMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = CancelFuturesOrder {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    orderbook: market.orderbook,
+                    event_queue: market.event_queue,
+                    bids: market.bids,
+                    asks: market.asks,
+                    quote_pool_node: market.quote_pool_node,
+                    authority: self.vault.to_account_info(),
+                };
+                cancel_futures_order(
+                    CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+                    args,
+                )
+            }
+            MarketType::Spot => {
+                let market = SpotMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = CancelSpotOrder {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    asset_pool_node: market.asset_pool_node,
+                    quote_pool_node: market.quote_pool_node,
+                    asset_mint: market.asset_mint,
+                    asset_vault: market.asset_vault,
+                    quote_vault: market.quote_vault,
+                    vault_signer: market.vault_signer,
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    event_queue: market.event_queue,
+                    bids: market.bids,
+                    asks: market.asks,
+                    coin_vault: market.coin_vault,
+                    pc_vault: market.pc_vault,
+                    dex_vault_signer: market.dex_vault_signer,
+                    authority: self.vault.to_account_info(),
+                    token_program: market.token_program,
+                    dex_program: market.dex_program,
+                };
+                cancel_spot_order(
+                    CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+                    args,
+                )
+            }
+        }
+    }
+
+    /// Invokes [`Cypher`]'s cancel all orders instruction for the given market type, signed by the [`Vault`].
+    pub fn invoke_cancel_all_orders(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        market_type: MarketType,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let vault_id = vault.id.to_le_bytes();
+        let signer: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            vault.creator.as_ref(),
+            vault_id.as_ref(),
+            &[vault.bump],
+        ]];
+        let cpi_program = self.cypher_program.to_account_info();
+
+        match market_type {
+            MarketType::Perpetual => {
+                let market = DerivativeMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = CancelPerpOrders {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    orderbook: market.orderbook,
+                    event_queue: market.event_queue,
+                    bids: market.bids,
+                    asks: market.asks,
+                    quote_pool_node: market.quote_pool_node,
+                    authority: self.vault.to_account_info(),
+                };
+                cancel_perp_orders(CpiContext::new_with_signer(
+                    cpi_program,
+                    cpi_accounts,
+                    signer,
+                ))
+            }
+            MarketType::Futures => {
+                let market = DerivativeMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = CancelFuturesOrders {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    orderbook: market.orderbook,
+                    event_queue: market.event_queue,
+                    bids: market.bids,
+                    asks: market.asks,
+                    quote_pool_node: market.quote_pool_node,
+                    authority: self.vault.to_account_info(),
+                };
+                cancel_futures_orders(CpiContext::new_with_signer(
+                    cpi_program,
+                    cpi_accounts,
+                    signer,
+                ))
+            }
+            MarketType::Spot => {
+                let market = SpotMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = CancelSpotOrders {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    asset_pool_node: market.asset_pool_node,
+                    quote_pool_node: market.quote_pool_node,
+                    asset_mint: market.asset_mint,
+                    asset_vault: market.asset_vault,
+                    quote_vault: market.quote_vault,
+                    vault_signer: market.vault_signer,
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    event_queue: market.event_queue,
+                    bids: market.bids,
+                    asks: market.asks,
+                    coin_vault: market.coin_vault,
+                    pc_vault: market.pc_vault,
+                    dex_vault_signer: market.dex_vault_signer,
+                    authority: self.vault.to_account_info(),
+                    token_program: market.token_program,
+                    dex_program: market.dex_program,
+                };
+                cancel_spot_orders(CpiContext::new_with_signer(
+                    cpi_program,
+                    cpi_accounts,
+                    signer,
+                ))
+            }
+        }
+    }
+
+    /// Invokes [`Cypher`]'s settle funds instruction for the given market type, signed by the [`Vault`].
+    pub fn invoke_settle_funds(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        market_type: MarketType,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
+        let vault_id = vault.id.to_le_bytes();
+        let signer: &[&[&[u8]]] = &[&[
+            VAULT_SEED,
+            vault.creator.as_ref(),
+            vault_id.as_ref(),
+            &[vault.bump],
+        ]];
+        let cpi_program = self.cypher_program.to_account_info();
+
+        match market_type {
+            MarketType::Perpetual => {
+                let market = DerivativeMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = SettlePerpFunds {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    quote_pool_node: market.quote_pool_node,
+                    authority: self.vault.to_account_info(),
+                };
+                settle_perp_funds(CpiContext::new_with_signer(
+                    cpi_program,
+                    cpi_accounts,
+                    signer,
+                ))
+            }
+            MarketType::Futures => {
+                let market = DerivativeMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = SettleFuturesFunds {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    quote_pool_node: market.quote_pool_node,
+                    authority: self.vault.to_account_info(),
+                };
+                settle_futures_funds(CpiContext::new_with_signer(
+                    cpi_program,
+                    cpi_accounts,
+                    signer,
+                ))
+            }
+            MarketType::Spot => {
+                let market = SpotMarketAccounts::try_from(remaining_accounts)?;
+                let cpi_accounts = SettleSpotFunds {
+                    clearing: self.clearing.to_account_info(),
+                    cache_account: self.cache_account.to_account_info(),
+                    master_account: self.cypher_account.to_account_info(),
+                    sub_account: self.cypher_sub_account.to_account_info(),
+                    asset_pool_node: market.asset_pool_node,
+                    quote_pool_node: market.quote_pool_node,
+                    asset_mint: market.asset_mint,
+                    asset_vault: market.asset_vault,
+                    quote_vault: market.quote_vault,
+                    vault_signer: market.vault_signer,
+                    market: market.market,
+                    open_orders: market.open_orders,
+                    coin_vault: market.coin_vault,
+                    pc_vault: market.pc_vault,
+                    dex_vault_signer: market.dex_vault_signer,
+                    authority: self.vault.to_account_info(),
+                    token_program: market.token_program,
+                    dex_program: market.dex_program,
+                };
+                settle_spot_funds(CpiContext::new_with_signer(
+                    cpi_program,
+                    cpi_accounts,
+                    signer,
+                ))
+            }
+        }
+    }
+
+    /// Emits the [`TradeExecuted`] event for a trading operation.
+    pub fn emit_trade_executed(
+        &self,
+        market_type: MarketType,
+        operation: TradeOperation,
+    ) -> Result<()> {
+        emit!(TradeExecuted {
+            vault: self.vault.key(),
+            manager: self.manager.key(),
+            market_type,
+            operation,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Gets the size of every position of the traded [`CypherSubAccount`], see [`Vault::get_position_sizes`].
+    pub fn get_position_sizes(&self) -> Result<Vec<(I80F48, I80F48)>> {
+        let sub_account = self.cypher_sub_account.load()?;
+        let cache_account = self.cache_account.load()?;
+        Ok(Vault::get_position_sizes(&sub_account, &cache_account))
+    }
+
+    /// Checks that a new order of a winding down [`Vault`] did not grow any position of the traded [`CypherSubAccount`].
+    ///
+    /// Once the [`Vault`] is winding down the manager can only close its positions, so its LPs can claim their share.
+    pub fn check_positions_reduced(&self, position_sizes: &[(I80F48, I80F48)]) -> Result<()> {
+        if !self.vault.load()?.is_winding_down() {
+            return Ok(());
+        }
+        for (before, after) in position_sizes.iter().zip(self.get_position_sizes()?) {
+            check!(
+                after.0 <= before.0 && after.1 <= before.1,
+                PositionIncreased
+            );
+        }
+        Ok(())
+    }
+
+    /// Gets the equity of the traded [`CypherSubAccount`], see [`Vault::get_sub_account_equity`].
+    pub fn get_equity(&self) -> Result<I80F48> {
+        let sub_account = self.cypher_sub_account.load()?;
+        let cache_account = self.cache_account.load()?;
+        Ok(Vault::get_sub_account_equity(
+            std::slice::from_ref(&sub_account),
+            &cache_account,
+        ))
+    }
+
+    /// Checks that a new order placed by anyone but the manager did not cost the traded [`CypherSubAccount`]
+    /// more than [`MAX_SETTLEMENT_SLIPPAGE_BPS`] of its equity, marked to the oracle prices.
+    ///
+    /// Anyone can only trade an overdue wind-down to close its positions, this keeps them from doing so
+    /// at prices that hand the [`Vault`]'s equity to themselves.
+    pub fn check_settlement_slippage(&self, equity_before: I80F48) -> Result<()> {
+        if self.manager.key() == self.vault.load()?.manager {
+            return Ok(());
+        }
+        let max_loss = equity_before.abs() * I80F48::from_num(MAX_SETTLEMENT_SLIPPAGE_BPS)
+            / I80F48::from_num(BPS_DENOMINATOR);
+        check!(
+            equity_before - self.get_equity()? <= max_loss,
+            SlippageExceeded
+        );
+        Ok(())
+    }
+
+    /// Checks the traded [`CypherSubAccount`] against its [`RiskParams`].
+    ///
+    /// This is done after every new order so that no trade can leave the [`Vault`] in breach of them.
+    /// Cancelling orders and settling funds can not add to the [`Vault`]'s risk, so they are not checked,
+    /// which lets the manager de-risk a sub account that is already in breach.
+    pub fn check_risk_limits(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        let risk_params = vault
+            .get_risk_params(&self.vault.key(), &self.cypher_sub_account.key())
+            .ok_or(ErrorCode::InvalidCypherAccount)?;
+        let sub_account = self.cypher_sub_account.load()?;
+        let cache_account = self.cache_account.load()?;
+        let clearing = self.clearing.load()?;
+        check_sub_account_risk_limits(&vault, risk_params, &sub_account, &cache_account, &clearing)
+    }
+}
+
+/// Checks one of the [`Vault`]'s [`CypherSubAccount`]s against the given [`RiskParams`].
+pub fn check_sub_account_risk_limits(
+    vault: &Vault,
+    risk_params: &RiskParams,
+    sub_account: &CypherSubAccount,
+    cache_account: &CacheAccount,
+    clearing: &Clearing,
+) -> Result<()> {
+    let max_market_notional = I80F48::from_num(risk_params.max_market_notional);
+    let mut gross_notional = I80F48::ZERO;
+
+    for position in sub_account.positions.iter() {
+        let derivative = &position.derivative;
+        if derivative.market != Pubkey::default() {
+            let base_position = derivative.total_position();
+            if base_position != I80F48::ZERO {
+                check!(
+                    risk_params.is_market_allowed(&derivative.market),
+                    MarketNotAllowed
+                );
+                let cache = cache_account.get_price_cache(derivative.cache_index as usize);
+                let notional = (base_position * cache.oracle_price()).abs();
+                check!(
+                    risk_params.max_market_notional == 0 || notional <= max_market_notional,
+                    MarketNotionalExceeded
+                );
+                gross_notional += notional;
+            }
+        }
+
+        // the quote token is not a market position
+        let spot = &position.spot;
+        if spot.token_mint != Pubkey::default() && spot.token_mint != quote_mint::ID {
+            let cache = cache_account.get_price_cache(spot.cache_index as usize);
+            let total_position = spot.total_position(cache);
+            if total_position != I80F48::ZERO {
+                // the tokens accepted for deposits can always be held
+                check!(
+                    risk_params.is_market_allowed(&spot.token_mint)
+                        || vault.get_token_info(spot.token_mint).is_some(),
+                    MarketNotAllowed
+                );
+                let notional = (total_position * cache.oracle_price()).abs();
+                check!(
+                    risk_params.max_market_notional == 0 || notional <= max_market_notional,
+                    MarketNotionalExceeded
+                );
+                gross_notional += notional;
+            }
+        }
+    }
+
+    if risk_params.max_leverage != 0 {
+        let equity =
+            Vault::get_sub_account_equity(std::slice::from_ref(&sub_account), cache_account);
+        check!(
+            equity > I80F48::ZERO
+                && gross_notional * I80F48::from_num(100)
+                    <= equity * I80F48::from_num(risk_params.max_leverage),
+            LeverageExceeded
+        );
+    }
+
+    // the margin ratio is unbounded without liabilities, so the percentage is scaled down instead
+    let min_margin_ratio = risk_params
+        .min_margin_ratio
+        .max(clearing.config.maint_margin as u16);
+    let margin_ratio =
+        sub_account.get_margin_c_ratio(cache_account, MarginCollateralRatioType::Maintenance);
+    check!(
+        margin_ratio >= I80F48::from_num(min_margin_ratio) / I80F48::from_num(100),
+        MarginRatioBreached
+    );
+
+    Ok(())
+}
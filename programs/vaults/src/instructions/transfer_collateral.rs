@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use cypher_client::{
+    cpi::{accounts::TransferBetweenSubAccounts, transfer_between_sub_accounts},
+    program::Cypher,
+    utils::{derive_account_address, derive_sub_account_address},
+    CacheAccount, Clearing, CypherAccount, CypherSubAccount, Pool,
+};
+
+use crate::{
+    check, check_sub_account_risk_limits, error::ErrorCode, events::CollateralTransferred, Vault,
+    VAULT_SEED,
+};
+
+#[derive(Accounts)]
+pub struct TransferCollateral<'info> {
+    #[account(
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.manager == manager.key()
+            || vault.load()?.is_wind_down_overdue(Clock::get()?.unix_timestamp)
+            @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub cache_account: AccountLoader<'info, CacheAccount>,
+
+    pub clearing: AccountLoader<'info, Clearing>,
+
+    #[account(mut)]
+    pub cypher_account: AccountLoader<'info, CypherAccount>,
+
+    #[account(mut)]
+    pub from_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    #[account(mut)]
+    pub to_sub_account: AccountLoader<'info, CypherSubAccount>,
+
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+
+    /// The [`Vault`]'s manager, or anyone once its wind-down is overdue, see [`Vault::is_wind_down_overdue`].
+    pub manager: Signer<'info>,
+
+    pub cypher_program: Program<'info, Cypher>,
+}
+
+impl<'info> TransferCollateral<'info> {
+    /// We need to validate that trading is not paused and that both sub accounts are the [`Vault`]'s.
+    ///
+    /// Anyone but the manager can only move the collateral of an overdue wind-down back into the primary
+    /// sub account, where the LPs claim it from, which is not held back by a pause.
+    pub fn validate(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        let (cypher_account, _) = derive_account_address(&self.vault.key(), vault.account_number);
+        if self.manager.key() == vault.manager {
+            check!(vault.pause_state().allows_trading(), VaultPaused);
+        } else {
+            let (primary_sub_account, _) =
+                derive_sub_account_address(&cypher_account, vault.sub_account_number);
+            check!(
+                self.to_sub_account.key() == primary_sub_account,
+                InvalidCypherAccount
+            );
+        }
+
+        check!(
+            self.cypher_account.key() == cypher_account,
+            InvalidCypherAccount
+        );
+        check!(
+            self.from_sub_account.key() != self.to_sub_account.key(),
+            InvalidCypherAccount
+        );
+        let sub_accounts = vault.get_sub_account_addresses(&self.vault.key());
+        check!(
+            sub_accounts.contains(&self.from_sub_account.key())
+                && sub_accounts.contains(&self.to_sub_account.key()),
+            InvalidCypherAccount
+        );
+        Ok(())
+    }
+
+    /// Invokes [`Cypher`]'s [`TransferBetweenSubAccounts`] instruction.
+    pub fn invoke_transfer_between_sub_accounts(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cpi_program = self.cypher_program.to_account_info();
+        let cpi_accounts = TransferBetweenSubAccounts {
+            clearing: self.clearing.to_account_info(),
+            cache_account: self.cache_account.to_account_info(),
+            master_account: self.cypher_account.to_account_info(),
+            from_sub_account: self.from_sub_account.to_account_info(),
+            to_sub_account: self.to_sub_account.to_account_info(),
+            pool: self.pool.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        transfer_between_sub_accounts(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[
+                    VAULT_SEED,
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+
+    /// Checks both sub accounts against their own [`crate::RiskParams`].
+    pub fn check_risk_limits(&self) -> Result<()> {
+        let vault = self.vault.load()?;
+        let cache_account = self.cache_account.load()?;
+        let clearing = self.clearing.load()?;
+        for sub_account in [&self.from_sub_account, &self.to_sub_account] {
+            let risk_params = vault
+                .get_risk_params(&self.vault.key(), &sub_account.key())
+                .ok_or(ErrorCode::InvalidCypherAccount)?;
+            check_sub_account_risk_limits(
+                &vault,
+                risk_params,
+                &sub_account.load()?,
+                &cache_account,
+                &clearing,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The manager moves `amount` of the pool's SPL Token between two of the [`Vault`]'s sub accounts,
+/// anyone can move it back into the primary sub account once the [`Vault`]'s wind-down is overdue.
+///
+/// The funds never leave the [`Vault`]'s [`CypherAccount`], and neither sub account can be left in breach of its risk limits.
+pub fn handler(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    ctx.accounts.invoke_transfer_between_sub_accounts(amount)?;
+
+    ctx.accounts.check_risk_limits()?;
+
+    emit!(CollateralTransferred {
+        vault: ctx.accounts.vault.key(),
+        manager: ctx.accounts.manager.key(),
+        from_sub_account: ctx.accounts.from_sub_account.key(),
+        to_sub_account: ctx.accounts.to_sub_account.key(),
+        pool: ctx.accounts.pool.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, PauseState, PauseStateSet, Vault};
+
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Only the authority can unpause the [`Vault`], resuming deposits, withdrawals and trading.
+pub fn handler(ctx: Context<Unpause>) -> Result<()> {
+    ctx.accounts
+        .vault
+        .load_mut()?
+        .set_pause_state(PauseState::Active);
+
+    emit!(PauseStateSet {
+        vault: ctx.accounts.vault.key(),
+        signer: ctx.accounts.authority.key(),
+        pause_state: PauseState::Active,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
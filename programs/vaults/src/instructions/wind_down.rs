@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{check, error::ErrorCode, Vault, WindDownStarted};
+
+#[derive(Accounts)]
+pub struct WindDown<'info> {
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+        constraint = vault.load()?.authority == authority.key() @ anchor_lang::error::ErrorCode::ConstraintHasOne,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+
+    pub authority: Signer<'info>,
+}
+
+impl<'info> WindDown<'info> {
+    /// We need to validate that the [`Vault`] is not already winding down.
+    pub fn validate(&self) -> Result<()> {
+        check!(!self.vault.load()?.is_winding_down(), VaultWindingDown);
+        Ok(())
+    }
+}
+
+/// The authority winds down the [`Vault`] once its strategy has ended, which can not be undone.
+///
+/// New deposits are no longer accepted and the manager is expected to close all positions, after which
+/// any LP can claim their pro-rata share of the remaining SPL Tokens through `claim_wind_down`.
+/// If the manager has not done so within [`crate::WIND_DOWN_SETTLEMENT_DELAY`], anyone can.
+pub fn handler(ctx: Context<WindDown>) -> Result<()> {
+    ctx.accounts.validate()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.vault.load_mut()?.wind_down_at = now;
+
+    emit!(WindDownStarted {
+        vault: ctx.accounts.vault.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
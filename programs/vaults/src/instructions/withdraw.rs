@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+use anchor_spl::token::{burn, mint_to, Burn, Mint, MintTo, Token, TokenAccount};
 use cypher_client::{
     cpi::{accounts::WithdrawFunds, withdraw_funds},
     program::Cypher,
@@ -7,23 +7,52 @@ use cypher_client::{
 };
 
 use crate::{
+    check,
     error::ErrorCode,
-    state::{Vault, VAULT_SEED},
+    events::{FeesAccrued, Withdrawn},
+    state::{DepositorPosition, Vault, DEPOSITOR_POSITION_SEED, VAULT_SEED},
 };
 
+/// The [`Vault`]'s strategy sub accounts are passed as writable remaining accounts, see [`Vault::load_sub_accounts`].
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
-    #[account(mut)]
-    pub vault: Box<Account<'info, Vault>>,
+    #[account(
+        mut,
+        constraint = Vault::is_migrated_account(&vault.to_account_info()) @ ErrorCode::VaultNotMigrated,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
 
+    #[account(mut)]
     pub lp_mint: Box<Account<'info, Mint>>,
 
     #[account(
+        mut,
         token::mint = lp_mint,
         token::authority = authority,
     )]
     pub lp_token_account: Box<Account<'info, TokenAccount>>,
 
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = vault.load()?.fee_recipient,
+    )]
+    pub fee_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            DEPOSITOR_POSITION_SEED,
+            vault.key().as_ref(),
+            token_mint.key().as_ref(),
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = DepositorPosition::LEN,
+    )]
+    pub depositor_position: Box<Account<'info, DepositorPosition>>,
+
     pub cache_account: AccountLoader<'info, CacheAccount>,
 
     pub clearing: AccountLoader<'info, Clearing>,
@@ -57,6 +86,11 @@ pub struct Withdraw<'info> {
 
     pub authority: Signer<'info>,
 
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
     pub token_program: Program<'info, Token>,
 
     pub cypher_program: Program<'info, Cypher>,
@@ -65,20 +99,115 @@ pub struct Withdraw<'info> {
 impl<'info> Withdraw<'info> {
     /// We need to validate that we have the correct SPL Token.
     pub fn validate(&self) -> Result<()> {
-        self.vault
+        let vault = self.vault.load()?;
+        let token_info = vault
             .get_token_info(self.token_mint.key())
             .ok_or(ErrorCode::InvalidTokenMint)?;
+        let share_info = vault.get_share_info(self.token_mint.key()).unwrap();
+        check!(self.lp_mint.key() == share_info.lp_mint, InvalidLpMint);
+        check!(vault.pause_state().allows_withdrawals(), VaultPaused);
+        check!(!vault.is_epoch_mode(), EpochModeEnabled);
+        // a vault-wide LP token can only be redeemed into enabled SPL Tokens
+        check!(
+            !vault.has_vault_lp() || token_info.is_enabled(),
+            TokenDisabled
+        );
         Ok(())
     }
 
+    /// Gets the marked to market value backing the LP token of the SPL Token being withdrawn.
+    pub fn get_share_net_assets(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let vault = self.vault.load()?;
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        vault.get_share_net_assets(&self.token_mint.key(), &sub_accounts, &cache_account)
+    }
+
+    /// Splits a withdrawal across the [`Vault`]'s [`CypherSubAccount`]s, see [`Vault::get_withdraw_amounts`].
+    pub fn get_withdraw_amounts(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        amount: u64,
+    ) -> Result<Vec<(AccountInfo<'info>, u64)>> {
+        let vault = self.vault.load()?;
+        let sub_account_loaders = vault.load_sub_accounts(
+            &self.vault.key(),
+            &self.cypher_sub_account,
+            remaining_accounts,
+        )?;
+        let sub_accounts = sub_account_loaders
+            .iter()
+            .map(|sa| sa.load())
+            .collect::<Result<Vec<_>>>()?;
+        let cache_account = self.cache_account.load()?;
+        let withdraw_amounts = Vault::get_withdraw_amounts(
+            &self.token_mint.key(),
+            amount,
+            &sub_accounts,
+            &cache_account,
+        );
+        Ok(sub_account_loaders
+            .iter()
+            .map(|sa| sa.to_account_info())
+            .zip(withdraw_amounts)
+            .filter(|(_, amount)| *amount != 0)
+            .collect())
+    }
+
+    /// Converts an amount of the SPL Token being withdrawn into the units its LP token is priced in.
+    pub fn to_share_units(&self, amount: u64) -> Result<u64> {
+        let vault = self.vault.load()?;
+        let cache_account = self.cache_account.load()?;
+        let token_info = vault.get_token_info(self.token_mint.key()).unwrap();
+        vault.to_share_units(token_info, amount, &cache_account)
+    }
+
+    /// Converts an amount in the units the LP token is priced in into the SPL Token being withdrawn.
+    pub fn from_share_units(&self, amount: u64) -> Result<u64> {
+        let vault = self.vault.load()?;
+        let cache_account = self.cache_account.load()?;
+        let token_info = vault.get_token_info(self.token_mint.key()).unwrap();
+        vault.from_share_units(token_info, amount, &cache_account)
+    }
+
+    /// Records the withdrawal in the depositor's position.
+    pub fn record_position_withdraw(
+        &mut self,
+        position_bump: u8,
+        withdraw_amount: u64,
+        burn_amount: u64,
+    ) {
+        let vault = self.vault.key();
+        let token_mint = self.token_mint.key();
+        let owner = self.authority.key();
+        let depositor_position = &mut self.depositor_position;
+        if depositor_position.owner == Pubkey::default() {
+            depositor_position.init(vault, token_mint, owner, position_bump);
+        }
+        depositor_position.record_withdraw(withdraw_amount, burn_amount);
+    }
+
     /// Withdraw the input amount from the [`cypher_client::CypherAccount`].
-    pub fn invoke_withdraw_funds(&self, amount: u64) -> Result<()> {
+    pub fn invoke_withdraw_funds(
+        &self,
+        sub_account: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        let vault = self.vault.load()?;
         let cpi_program = self.cypher_program.to_account_info();
         let cpi_accounts = WithdrawFunds {
             clearing: self.clearing.to_account_info(),
             cache_account: self.cache_account.to_account_info(),
             master_account: self.cypher_account.to_account_info(),
-            sub_account: self.cypher_sub_account.to_account_info(),
+            sub_account,
             pool: self.pool.to_account_info(),
             pool_node: self.pool_node.to_account_info(),
             token_vault: self.token_vault.to_account_info(),
@@ -94,67 +223,133 @@ impl<'info> Withdraw<'info> {
                 cpi_accounts,
                 &[&[
                     VAULT_SEED,
-                    self.vault.authority.as_ref(),
-                    self.vault.id.to_le_bytes().as_ref(),
-                    &[self.vault.bump],
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
                 ]],
             ),
             amount,
         )
     }
 
-    /// Burn a corresponding amount of LP tokens.
-    pub fn invoke_burn(&self, amount: u64) -> Result<()> {
+    /// Mint the accrued fees to the fee recipient.
+    pub fn invoke_mint_fees(&self, amount: u64) -> Result<()> {
+        let vault = self.vault.load()?;
         let cpi_program = self.token_program.to_account_info();
-        let cpi_accounts = Burn {
+        let cpi_accounts = MintTo {
             mint: self.lp_mint.to_account_info(),
-            from: self.lp_token_account.to_account_info(),
+            to: self.fee_token_account.to_account_info(),
             authority: self.vault.to_account_info(),
         };
-        burn(
+        mint_to(
             CpiContext::new_with_signer(
                 cpi_program,
                 cpi_accounts,
                 &[&[
                     VAULT_SEED,
-                    self.vault.authority.as_ref(),
-                    self.vault.id.to_le_bytes().as_ref(),
-                    &[self.vault.bump],
+                    vault.creator.as_ref(),
+                    vault.id.to_le_bytes().as_ref(),
+                    &[vault.bump],
                 ]],
             ),
             amount,
         )
     }
+
+    /// Burn a corresponding amount of LP tokens, signed by the LP holder.
+    pub fn invoke_burn(&self, amount: u64) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Burn {
+            mint: self.lp_mint.to_account_info(),
+            from: self.lp_token_account.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        burn(CpiContext::new(cpi_program, cpi_accounts), amount)
+    }
 }
 
 /// The user wants to withdraw a token amount represented by `withdraw_amount`,
 /// taking this number we need to calculate how many tokens we are going to burn for the user.
-pub fn handler(ctx: Context<Withdraw>, withdraw_amount: u64) -> Result<()> {
+///
+/// The withdrawal fails if more than `max_lp_burn` LP tokens would be burned.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+    withdraw_amount: u64,
+    max_lp_burn: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
     ctx.accounts.validate()?;
 
+    // value the [`Vault`]'s holdings before the withdrawal leaves the [`CypherSubAccount`]
+    let net_assets = ctx.accounts.get_share_net_assets(ctx.remaining_accounts)?;
+    let share_amount = ctx.accounts.to_share_units(withdraw_amount)?;
+    check!(share_amount != 0, ZeroAmount);
+
+    // accrue the fees up until now so the withdrawer pays their share of them
+    let token_mint = ctx.accounts.token_mint.key();
+    let fee_amount = ctx
+        .accounts
+        .vault
+        .load_mut()?
+        .accrue_fees(token_mint, net_assets, now)?;
+    if fee_amount != 0 {
+        ctx.accounts.invoke_mint_fees(fee_amount)?;
+    }
+    let share_price = ctx
+        .accounts
+        .vault
+        .load()?
+        .get_share_price(token_mint, net_assets)?;
+    emit!(FeesAccrued {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        fee_recipient: ctx.accounts.vault.load()?.fee_recipient,
+        lp_minted: fee_amount,
+        share_price,
+        timestamp: now,
+    });
+
     let burn_amount: u64 = ctx
         .accounts
         .vault
-        .get_token_info(ctx.accounts.token_mint.key())
+        .load()?
+        .get_share_info(token_mint)
         .unwrap()
-        .calculate_burn_amount(withdraw_amount as u128)
-        .try_into()
-        .unwrap();
+        .calculate_burn_amount(share_amount, net_assets)?;
+    check!(burn_amount <= max_lp_burn, SlippageExceeded);
 
     // burn the corresponding amount
-    ctx.accounts.invoke_burn(burn_amount as u64)?;
+    ctx.accounts.invoke_burn(burn_amount)?;
 
-    // finally withdraw from the [`Vault`]'s [`CypherAccount`]
-    ctx.accounts.invoke_withdraw_funds(withdraw_amount)?;
-
-    let vault = &mut ctx.accounts.vault;
-    let token_info = vault
-        .get_token_info_mut(ctx.accounts.token_mint.key())
-        .unwrap();
+    // finally withdraw from the [`Vault`]'s [`CypherAccount`], spread across its sub accounts
+    for (sub_account, amount) in ctx
+        .accounts
+        .get_withdraw_amounts(ctx.remaining_accounts, withdraw_amount)?
+    {
+        ctx.accounts.invoke_withdraw_funds(sub_account, amount)?;
+    }
 
     // update the [`Vault`]'s data
-    token_info.token_supply = token_info.token_supply.checked_sub(burn_amount).unwrap();
-    token_info.deposits = token_info.deposits.checked_sub(withdraw_amount).unwrap();
+    ctx.accounts
+        .vault
+        .load_mut()?
+        .record_withdraw(token_mint, withdraw_amount, burn_amount)?;
+
+    // record the withdrawal in the depositor's position
+    let position_bump = *ctx.bumps.get("depositor_position").unwrap();
+    ctx.accounts
+        .record_position_withdraw(position_bump, withdraw_amount, burn_amount);
+
+    emit!(Withdrawn {
+        vault: ctx.accounts.vault.key(),
+        token_mint,
+        owner: ctx.accounts.authority.key(),
+        amount: withdraw_amount,
+        lp_burned: burn_amount,
+        share_price,
+        timestamp: now,
+    });
 
     Ok(())
 }
@@ -1,11 +1,14 @@
-mod error;
+pub mod error;
+mod events;
 mod instructions;
 mod state;
 
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
 use anchor_lang::prelude::*;
+use cypher_client::CancelOrderArgs;
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
@@ -13,20 +16,90 @@ declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 pub mod vaults {
     use super::*;
 
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority::handler(ctx)
+    }
+
+    pub fn accrue_fees<'info>(ctx: Context<'_, '_, '_, 'info, AccrueFees<'info>>) -> Result<()> {
+        instructions::accrue_fees::handler(ctx)
+    }
+
+    pub fn approve_deposits_for(ctx: Context<ApproveDepositsFor>, approved: bool) -> Result<()> {
+        instructions::approve_deposits_for::handler(ctx, approved)
+    }
+
+    pub fn cancel_all_orders<'info>(
+        ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+        market_type: MarketType,
+    ) -> Result<()> {
+        instructions::cancel_all_orders::handler(ctx, market_type)
+    }
+
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        instructions::cancel_config_change::handler(ctx)
+    }
+
+    pub fn cancel_epoch_ticket(ctx: Context<CancelEpochTicket>) -> Result<()> {
+        instructions::cancel_epoch_ticket::handler(ctx)
+    }
+
+    pub fn cancel_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+        market_type: MarketType,
+        args: CancelOrderArgs,
+    ) -> Result<()> {
+        instructions::cancel_order::handler(ctx, market_type, args)
+    }
+
+    pub fn claim_epoch(ctx: Context<ClaimEpoch>) -> Result<()> {
+        instructions::claim_epoch::handler(ctx)
+    }
+
+    pub fn claim_wind_down<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimWindDown<'info>>,
+        lp_amount: u64,
+    ) -> Result<()> {
+        instructions::claim_wind_down::handler(ctx, lp_amount)
+    }
+
+    pub fn create_strategy_sub_account(
+        ctx: Context<CreateStrategySubAccount>,
+        args: CreateStrategySubAccountArgs,
+    ) -> Result<()> {
+        instructions::create_strategy_sub_account::handler(ctx, args)
+    }
+
     pub fn create_vault(ctx: Context<CreateVault>, args: CreateVaultArgs) -> Result<()> {
         instructions::create_vault::handler(ctx, args)
     }
 
+    pub fn create_vault_lp_mint(ctx: Context<CreateVaultLpMint>, decimals: u8) -> Result<()> {
+        instructions::create_vault_lp_mint::handler(ctx, decimals)
+    }
+
     pub fn close_deposits(ctx: Context<CloseDeposits>, token_mint: Pubkey) -> Result<()> {
         instructions::close_deposits::handler(ctx, token_mint)
     }
 
-    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+    pub fn close_vault<'info>(ctx: Context<'_, '_, '_, 'info, CloseVault<'info>>) -> Result<()> {
         instructions::close_vault::handler(ctx)
     }
 
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        instructions::deposit::handler(ctx, amount)
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
+        amount: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        instructions::deposit::handler(ctx, amount, min_lp_out)
+    }
+
+    pub fn deposit_for<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositFor<'info>>,
+        beneficiary: Pubkey,
+        amount: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        instructions::deposit_for::handler(ctx, beneficiary, amount, min_lp_out)
     }
 
     pub fn disable_deposits(ctx: Context<DisableDeposits>, token_mint: Pubkey) -> Result<()> {
@@ -37,10 +110,87 @@ pub mod vaults {
         instructions::enable_deposits::handler(ctx, token_mint)
     }
 
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::execute_config_change::handler(ctx)
+    }
+
+    pub fn migrate_vault<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateVault<'info>>,
+        args: MigrateVaultArgs,
+    ) -> Result<()> {
+        instructions::migrate_vault::handler(ctx, args)
+    }
+
+    pub fn new_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+        args: NewOrderArgs,
+    ) -> Result<()> {
+        instructions::new_order::handler(ctx, args)
+    }
+
     pub fn open_deposits(ctx: Context<OpenDeposits>, args: OpenDepositsArgs) -> Result<()> {
         instructions::open_deposits::handler(ctx, args)
     }
 
+    pub fn pause(ctx: Context<Pause>, withdraw_only: bool) -> Result<()> {
+        instructions::pause::handler(ctx, withdraw_only)
+    }
+
+    pub fn process_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProcessWithdraw<'info>>,
+    ) -> Result<()> {
+        instructions::process_withdraw::handler(ctx)
+    }
+
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        pending_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_authority::handler(ctx, pending_authority)
+    }
+
+    pub fn queue_config_change(
+        ctx: Context<QueueConfigChange>,
+        change: ConfigChange,
+    ) -> Result<()> {
+        instructions::queue_config_change::handler(ctx, change)
+    }
+
+    pub fn queue_deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, QueueDeposit<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::queue_deposit::handler(ctx, amount)
+    }
+
+    pub fn queue_withdraw(ctx: Context<QueueWithdraw>, lp_amount: u64) -> Result<()> {
+        instructions::queue_withdraw::handler(ctx, lp_amount)
+    }
+
+    pub fn redeem<'info>(
+        ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+        lp_amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        instructions::redeem::handler(ctx, lp_amount, min_tokens_out)
+    }
+
+    pub fn release_lockup(ctx: Context<ReleaseLockup>) -> Result<()> {
+        instructions::release_lockup::handler(ctx)
+    }
+
+    pub fn request_withdraw(
+        ctx: Context<RequestWithdraw>,
+        lp_amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        instructions::request_withdraw::handler(ctx, lp_amount, min_tokens_out)
+    }
+
+    pub fn roll_epoch<'info>(ctx: Context<'_, '_, '_, 'info, RollEpoch<'info>>) -> Result<()> {
+        instructions::roll_epoch::handler(ctx)
+    }
+
     pub fn set_deposit_limit(
         ctx: Context<SetDepositLimit>,
         token_mint: Pubkey,
@@ -49,7 +199,76 @@ pub mod vaults {
         instructions::set_deposit_limit::handler(ctx, token_mint, amount)
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-        instructions::withdraw::handler(ctx, amount)
+    pub fn set_fees(ctx: Context<SetFees>, args: SetFeesArgs) -> Result<()> {
+        instructions::set_fees::handler(ctx, args)
+    }
+
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        instructions::set_guardian::handler(ctx, guardian)
+    }
+
+    pub fn set_lockup_period(
+        ctx: Context<SetLockupPeriod>,
+        token_mint: Pubkey,
+        lockup_period: i64,
+    ) -> Result<()> {
+        instructions::set_lockup_period::handler(ctx, token_mint, lockup_period)
+    }
+
+    pub fn set_manager(ctx: Context<SetManager>, manager: Pubkey) -> Result<()> {
+        instructions::set_manager::handler(ctx, manager)
+    }
+
+    pub fn set_max_withdraw_wait(
+        ctx: Context<SetMaxWithdrawWait>,
+        max_withdraw_wait: i64,
+    ) -> Result<()> {
+        instructions::set_max_withdraw_wait::handler(ctx, max_withdraw_wait)
+    }
+
+    pub fn set_risk_params(
+        ctx: Context<SetRiskParams>,
+        sub_account_number: u8,
+        risk_params: RiskParams,
+    ) -> Result<()> {
+        instructions::set_risk_params::handler(ctx, sub_account_number, risk_params)
+    }
+
+    pub fn set_vault_deposit_limit(
+        ctx: Context<SetVaultDepositLimit>,
+        quote_deposit_limit: u64,
+    ) -> Result<()> {
+        instructions::set_vault_deposit_limit::handler(ctx, quote_deposit_limit)
+    }
+
+    pub fn settle_funds<'info>(
+        ctx: Context<'_, '_, '_, 'info, Trade<'info>>,
+        market_type: MarketType,
+    ) -> Result<()> {
+        instructions::settle_funds::handler(ctx, market_type)
+    }
+
+    pub fn sweep_dust(ctx: Context<SweepDust>) -> Result<()> {
+        instructions::sweep_dust::handler(ctx)
+    }
+
+    pub fn transfer_collateral(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
+        instructions::transfer_collateral::handler(ctx, amount)
+    }
+
+    pub fn unpause(ctx: Context<Unpause>) -> Result<()> {
+        instructions::unpause::handler(ctx)
+    }
+
+    pub fn wind_down(ctx: Context<WindDown>) -> Result<()> {
+        instructions::wind_down::handler(ctx)
+    }
+
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+        amount: u64,
+        max_lp_burn: u64,
+    ) -> Result<()> {
+        instructions::withdraw::handler(ctx, amount, max_lp_burn)
     }
 }
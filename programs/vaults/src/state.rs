@@ -1,16 +1,123 @@
+use std::ops::Deref;
+
 use anchor_lang::prelude::*;
+use cypher_client::{
+    quote_mint,
+    utils::{derive_account_address, derive_sub_account_address},
+    CacheAccount, Clearing, CypherSubAccount, NewDerivativeOrderArgs, NewSpotOrderArgs,
+};
+use fixed::types::I80F48;
 use jet_proto_proc_macros::assert_size;
 
+use crate::{check, error::ErrorCode};
+
 /// The seed for the PDA of the [`Vault`].
 pub const VAULT_SEED: &[u8] = b"VAULT";
 
 /// The seed for the PDA of a [`Vault`]s LP token.
 pub const LP_TOKEN_SEED: &[u8] = b"LP_TOKEN";
 
+/// The seed for the PDA of a [`Vault`]'s vault-wide LP token, see [`Vault::lp_info`].
+///
+/// This differs from [`LP_TOKEN_SEED`] since the LP token of the original layout was derived from that and the [`Vault`] alone.
+pub const VAULT_LP_TOKEN_SEED: &[u8] = b"VAULT_LP_TOKEN";
+
+/// The seed for the PDA of a [`WithdrawTicket`].
+pub const WITHDRAW_TICKET_SEED: &[u8] = b"WITHDRAW_TICKET";
+
+/// The seed for the PDA of a [`WithdrawTicket`]'s LP token escrow.
+pub const WITHDRAW_ESCROW_SEED: &[u8] = b"WITHDRAW_ESCROW";
+
+/// The seed for the PDA of a [`DepositorPosition`].
+pub const DEPOSITOR_POSITION_SEED: &[u8] = b"DEPOSITOR_POSITION";
+
+/// The seed for the PDA of a [`DepositorPosition`]'s LP token escrow.
+///
+/// This holds the LP tokens received for deposits until their lockup period has passed.
+pub const LOCKUP_ESCROW_SEED: &[u8] = b"LOCKUP_ESCROW";
+
+/// The seed for the PDA of an [`EpochTicket`].
+pub const EPOCH_TICKET_SEED: &[u8] = b"EPOCH_TICKET";
+
+/// The seed for the PDA of an [`EpochRecord`].
+pub const EPOCH_RECORD_SEED: &[u8] = b"EPOCH_RECORD";
+
+/// The seed for the PDA of an epoch [`Vault`]'s SPL Token escrow.
+///
+/// This holds the deposits queued in the current epoch and the withdrawals not yet claimed.
+pub const EPOCH_TOKEN_ESCROW_SEED: &[u8] = b"EPOCH_TOKEN_ESCROW";
+
+/// The seed for the PDA of an epoch [`Vault`]'s LP token escrow.
+///
+/// This holds the withdrawals queued in the current epoch and the LP tokens not yet claimed.
+pub const EPOCH_LP_ESCROW_SEED: &[u8] = b"EPOCH_LP_ESCROW";
+
+/// The seed for the PDA of a [`PendingConfigChange`].
+pub const CONFIG_CHANGE_SEED: &[u8] = b"CONFIG_CHANGE";
+
+/// The current version of the [`Vault`] account layout.
+///
+/// Version zero is the original layout, which has to be migrated with `migrate_vault`.
+pub const VAULT_VERSION: u8 = 1;
+
+/// The maximum number of SPL Tokens a [`Vault`] can accept.
+pub const MAX_TOKEN_INFOS: usize = 32;
+
+/// The maximum number of [`StrategySubAccount`]s a [`Vault`] can have on top of its primary sub account.
+pub const MAX_STRATEGY_SUB_ACCOUNTS: usize = 4;
+
+/// The maximum number of markets in a [`RiskParams`] allowlist.
+pub const MAX_ALLOWED_MARKETS: usize = 8;
+
+/// The denominator for all rates expressed in basis points.
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+/// The number of decimals an LP token has on top of the units it is priced in.
+pub const DECIMALS_OFFSET: u32 = 3;
+
+/// The virtual LP tokens added to a [`TokenInfo`]'s supply when pricing LP tokens.
+///
+/// This is scaled by [`DECIMALS_OFFSET`], so an attacker would have to donate that many times
+/// the first deposit to round it down to zero LP tokens.
+pub const VIRTUAL_SHARES: u128 = 10u128.pow(DECIMALS_OFFSET);
+
+/// The virtual assets added to a [`TokenInfo`]'s net assets when pricing LP tokens.
+pub const VIRTUAL_ASSETS: u128 = 1;
+
+/// The number of seconds in a year, used to accrue annualized fees.
+pub const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+
+/// The time, in seconds, the manager has to close the positions of a winding down [`Vault`] before anyone can,
+/// see [`Vault::is_wind_down_overdue`].
+pub const WIND_DOWN_SETTLEMENT_DELAY: i64 = 7 * 24 * 60 * 60;
+
+/// The maximum share of a [`CypherSubAccount`]'s equity, in basis points, a new order placed by anyone
+/// but the manager of an overdue wind-down may cost it.
+pub const MAX_SETTLEMENT_SLIPPAGE_BPS: u128 = 100;
+
+/// The scale of a [`TokenInfo`]'s share price, i.e. the value of one native unit of its LP token.
+pub const SHARE_PRICE_SCALE: u128 = 1_000_000_000;
+
+/// Calculates `a * b / c`, rounding up or down, returning an error if the result does not fit a [`u64`].
+fn mul_div(a: u128, b: u128, c: u128, round_up: bool) -> Result<u64> {
+    let numerator = a.checked_mul(b).ok_or(ErrorCode::MathOverflow)?;
+    let result = if round_up {
+        numerator
+            .checked_add(c - 1)
+            .ok_or(ErrorCode::MathOverflow)?
+            / c
+    } else {
+        numerator / c
+    };
+    u64::try_from(result).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
 #[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
 pub struct CreateVaultArgs {
     /// The id of the [`Vault`].
     pub id: u64,
+    /// The vault type.
+    pub vault_type: VaultType,
     /// The number of the [`cypher_client::CypherSubAccount`].
     pub account_number: u8,
     /// The bump of the [`cypher_client::CypherAccount`].
@@ -21,8 +128,53 @@ pub struct CreateVaultArgs {
     pub sub_account_bump: u8,
     /// The alias of the [`cypher_client::CypherSubAccount`].
     pub sub_account_alias: [u8; 32],
-    /// The number of SPL Tokens to support deposits for.
-    pub token_info_count: usize,
+    /// The annualized management fee, in basis points.
+    pub management_fee_bps: u16,
+    /// The performance fee charged on gains above the high-water mark, in basis points.
+    pub performance_fee_bps: u16,
+    /// The recipient of the fees.
+    pub fee_recipient: Pubkey,
+    /// The manager of the [`Vault`].
+    pub manager: Pubkey,
+    /// The maximum time, in seconds, a [`WithdrawTicket`] can wait before anyone can settle it.
+    pub max_withdraw_wait: i64,
+    /// The vault-wide deposit limit, in native units of the quote token.
+    pub quote_deposit_limit: u64,
+    /// The duration of an epoch, in seconds.
+    ///
+    /// A value of zero creates a regular [`Vault`], anything else an epoch [`Vault`] where
+    /// deposits and withdrawals are queued until the epoch is rolled.
+    pub epoch_duration: i64,
+    /// The delay, in seconds, before a queued [`ConfigChange`] can be executed.
+    pub config_timelock: i64,
+}
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
+pub struct MigrateVaultArgs {
+    /// The id of the [`Vault`], which the original layout did not store.
+    pub id: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct SetFeesArgs {
+    /// The annualized management fee, in basis points.
+    pub management_fee_bps: u16,
+    /// The performance fee charged on gains above the high-water mark, in basis points.
+    pub performance_fee_bps: u16,
+    /// The recipient of the fees.
+    pub fee_recipient: Pubkey,
+}
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
+pub struct CreateStrategySubAccountArgs {
+    /// The number of the [`cypher_client::CypherSubAccount`].
+    pub sub_account_number: u8,
+    /// The bump of the [`cypher_client::CypherSubAccount`].
+    pub sub_account_bump: u8,
+    /// The alias of the [`cypher_client::CypherSubAccount`].
+    pub sub_account_alias: [u8; 32],
+    /// The risk limits enforced after every trade in the [`cypher_client::CypherSubAccount`].
+    pub risk_params: RiskParams,
 }
 
 #[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
@@ -32,24 +184,271 @@ pub struct OpenDepositsArgs {
     /// The deposit limit for this SPL Token.
     pub deposit_limit: u64,
     /// The decimals of the [`Vault`]s LP token for this SPL Token Mint.
+    ///
+    /// The first deposit mints [`VIRTUAL_SHARES`] LP tokens per native unit, so these are expected
+    /// to be the decimals of the units the LP token is priced in plus [`DECIMALS_OFFSET`].
     pub decimals: u8,
+    /// The time, in seconds, deposits of this SPL Token are locked for.
+    pub lockup_period: i64,
+    /// The index of this SPL Token's price cache in the [`CacheAccount`].
+    pub cache_index: u8,
+}
+
+#[zero_copy]
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+#[repr(C)]
+pub struct RiskParams {
+    /// The maximum leverage, as a percentage, e.g. 300 is 3x.
+    ///
+    /// A value of zero means there is no leverage limit.
+    pub max_leverage: u16,
+    /// The minimum margin ratio, as a percentage, which can not be lower than Cypher's maintenance margin.
+    pub min_margin_ratio: u16,
+    padding: [u8; 4],
+    /// The maximum notional of a single market, in native units of the quote token.
+    ///
+    /// A value of zero means there is no notional limit.
+    pub max_market_notional: u64,
+    /// The markets the [`Vault`] is allowed to hold positions in.
+    ///
+    /// These are the market addresses of derivative markets and the base token mints of spot markets.
+    /// Unused entries are the default [`Pubkey`], so by default the allowlist is empty, which means the
+    /// [`Vault`] can only hold the SPL Tokens it accepts for deposits and no derivative positions at all,
+    /// every other market has to be allowed explicitly before it can be traded.
+    pub allowed_markets: [Pubkey; MAX_ALLOWED_MARKETS],
 }
 
+/// An additional [`CypherSubAccount`] of a [`Vault`], which isolates the margin of a strategy.
+#[zero_copy]
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize)]
+#[repr(C)]
+pub struct StrategySubAccount {
+    /// The number of the [`CypherSubAccount`].
+    pub sub_account_number: u8,
+    padding: [u8; 7],
+    /// The alias of the [`CypherSubAccount`].
+    pub alias: [u8; 32],
+    /// The risk limits enforced after every trade in the [`CypherSubAccount`].
+    pub risk_params: RiskParams,
+}
+
+impl StrategySubAccount {
+    /// Creates a new [`StrategySubAccount`].
+    pub fn new(args: &CreateStrategySubAccountArgs) -> Self {
+        Self {
+            sub_account_number: args.sub_account_number,
+            alias: args.sub_account_alias,
+            risk_params: args.risk_params,
+            ..Default::default()
+        }
+    }
+}
+
+impl RiskParams {
+    /// Validates that the minimum margin ratio is not below Cypher's maintenance margin.
+    pub fn validate(&self, clearing: &Clearing) -> Result<()> {
+        check!(
+            self.min_margin_ratio >= clearing.config.maint_margin as u16,
+            InvalidMarginRatio
+        );
+        Ok(())
+    }
+
+    /// Whether the given market is in the allowlist.
+    pub fn is_market_allowed(&self, market: &Pubkey) -> bool {
+        *market != Pubkey::default() && self.allowed_markets.contains(market)
+    }
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum MarketType {
+    /// A Cypher perpetual futures market.
+    Perpetual,
+    /// A Cypher fixed expiry futures market.
+    Futures,
+    /// A Serum spot market traded through Cypher.
+    Spot,
+}
+
+/// The arguments of a new order, for the [`cypher_client`] instruction of the market type it is placed on.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub enum NewOrderArgs {
+    /// A new order on a Cypher perpetual futures market.
+    Perpetual(NewDerivativeOrderArgs),
+    /// A new order on a Cypher fixed expiry futures market.
+    Futures(NewDerivativeOrderArgs),
+    /// A new order on a Serum spot market traded through Cypher.
+    Spot(NewSpotOrderArgs),
+}
+
+impl NewOrderArgs {
+    /// Gets the [`MarketType`] the order is placed on.
+    pub fn market_type(&self) -> MarketType {
+        match self {
+            NewOrderArgs::Perpetual(_) => MarketType::Perpetual,
+            NewOrderArgs::Futures(_) => MarketType::Futures,
+            NewOrderArgs::Spot(_) => MarketType::Spot,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum TradeOperation {
+    /// Places a new order.
+    NewOrder,
+    /// Cancels an order.
+    CancelOrder,
+    /// Cancels all orders.
+    CancelAllOrders,
+    /// Settles the funds of filled orders.
+    SettleFunds,
+}
+
+impl TradeOperation {
+    /// Whether the operation can only reduce the [`Vault`]'s risk.
+    pub fn is_risk_reducing(&self) -> bool {
+        *self != TradeOperation::NewOrder
+    }
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum PauseState {
+    /// The vault operates normally.
+    #[default]
+    Active,
+    /// Only withdrawals are allowed, deposits and new orders are blocked.
+    WithdrawOnly,
+    /// Deposits, withdrawals and new orders are all blocked.
+    Paused,
+}
+
+impl PauseState {
+    /// Whether deposits are allowed.
+    pub fn allows_deposits(&self) -> bool {
+        *self == PauseState::Active
+    }
+
+    /// Whether withdrawals are allowed.
+    pub fn allows_withdrawals(&self) -> bool {
+        *self != PauseState::Paused
+    }
+
+    /// Whether new orders are allowed, cancels and settles always are.
+    pub fn allows_trading(&self) -> bool {
+        *self == PauseState::Active
+    }
+}
+
+/// A configuration change which has to wait for the [`Vault`]'s timelock, see [`PendingConfigChange`].
 #[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum ConfigChange {
+    /// Sets the deposit limit of an SPL Token.
+    DepositLimit {
+        token_mint: Pubkey,
+        deposit_limit: u64,
+    },
+    /// Sets the fees and their recipient.
+    Fees { args: SetFeesArgs },
+    /// Sets the manager.
+    Manager { manager: Pubkey },
+    /// Sets the guardian.
+    Guardian { guardian: Pubkey },
+    /// Proposes a new authority, which still has to accept through `accept_authority`.
+    PendingAuthority { pending_authority: Pubkey },
+    /// Sets the timelock of later configuration changes.
+    ConfigTimelock { config_timelock: i64 },
+    /// Sets the risk limits of one of the [`Vault`]'s sub accounts.
+    RiskParams {
+        sub_account_number: u8,
+        risk_params: RiskParams,
+    },
+    /// Sets the vault-wide deposit limit.
+    VaultDepositLimit { quote_deposit_limit: u64 },
+    /// Sets the lockup period of an SPL Token.
+    LockupPeriod {
+        token_mint: Pubkey,
+        lockup_period: i64,
+    },
+    /// Sets the maximum time a [`WithdrawTicket`] can wait before anyone can settle it.
+    MaxWithdrawWait { max_withdraw_wait: i64 },
+}
+
+impl ConfigChange {
+    /// Validates the [`ConfigChange`] against the [`Vault`] it is applied to.
+    pub fn validate(&self, vault: &Vault, clearing: &Clearing) -> Result<()> {
+        match self {
+            ConfigChange::DepositLimit { token_mint, .. } => {
+                check!(
+                    vault.get_token_info(*token_mint).is_some(),
+                    InvalidTokenMint
+                );
+            }
+            ConfigChange::Fees { args } => {
+                check!(
+                    args.management_fee_bps as u128 <= BPS_DENOMINATOR,
+                    InvalidFee
+                );
+                check!(
+                    args.performance_fee_bps as u128 <= BPS_DENOMINATOR,
+                    InvalidFee
+                );
+            }
+            ConfigChange::ConfigTimelock { config_timelock } => {
+                check!(*config_timelock >= 0, InvalidConfigTimelock);
+            }
+            ConfigChange::RiskParams {
+                sub_account_number,
+                risk_params,
+            } => {
+                check!(
+                    vault.sub_account_numbers().contains(sub_account_number),
+                    InvalidCypherAccount
+                );
+                risk_params.validate(clearing)?;
+            }
+            ConfigChange::LockupPeriod {
+                token_mint,
+                lockup_period,
+            } => {
+                check!(
+                    vault.get_token_info(*token_mint).is_some(),
+                    InvalidTokenMint
+                );
+                check!(*lockup_period >= 0, InvalidLockupPeriod);
+            }
+            ConfigChange::MaxWithdrawWait { max_withdraw_wait } => {
+                check!(*max_withdraw_wait >= 0, InvalidMaxWithdrawWait);
+            }
+            ConfigChange::Manager { .. }
+            | ConfigChange::Guardian { .. }
+            | ConfigChange::PendingAuthority { .. }
+            | ConfigChange::VaultDepositLimit { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
 pub enum VaultType {
     /// The vault only allows deposits for a single token.
+    #[default]
     SingleToken,
     /// The vault allows deposits for multiple tokens.
     MultiToken,
 }
 
-#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
-#[assert_size(64, aligns)]
+#[zero_copy]
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize)]
+#[assert_size(120, aligns)]
 #[repr(C)]
 pub struct TokenInfo {
     /// Whether deposits of this SPL Token are currently enabled or not.
-    pub enabled: bool, // 1
-    padding: [u8; 7], // 8
+    enabled: u8, // 1
+    /// The index of this SPL Token's price cache in the [`CacheAccount`].
+    pub cache_index: u8, // 2
+    padding: [u8; 6], // 8
 
     /// The amount of deposits for this SPL Token.
     pub deposits: u64, // 16
@@ -60,9 +459,68 @@ pub struct TokenInfo {
 
     /// The address of the SPL Token Mint.
     pub token_mint: Pubkey, // 64
+
+    /// The highest share price on which performance fees have been charged.
+    ///
+    /// This is scaled by [`SHARE_PRICE_SCALE`].
+    pub high_water_mark: u64, // 72
+    /// The timestamp of the last fee accrual.
+    pub last_fee_accrual: i64, // 80
+    /// The time, in seconds, deposits of this SPL Token are locked for.
+    pub lockup_period: i64, // 88
+
+    /// The address of the LP token Mint for this SPL Token.
+    pub lp_mint: Pubkey, // 120
 }
 
 impl TokenInfo {
+    /// Creates a new [`TokenInfo`] with deposits enabled.
+    pub fn new(args: &OpenDepositsArgs, lp_mint: Pubkey) -> Self {
+        Self {
+            enabled: u8::from(true),
+            cache_index: args.cache_index,
+            padding: [0; 6],
+            deposits: 0,
+            deposit_limit: args.deposit_limit,
+            token_supply: 0,
+            token_mint: args.token_mint,
+            high_water_mark: 0,
+            last_fee_accrual: 0,
+            lockup_period: args.lockup_period,
+            lp_mint,
+        }
+    }
+
+    /// Creates a new [`TokenInfo`] for the vault-wide LP token of a [`VaultType::MultiToken`] vault.
+    ///
+    /// All of its amounts are denominated in native units of the quote token,
+    /// it is not backed by a single SPL Token so it has no [`TokenInfo::token_mint`].
+    pub fn new_vault_lp(lp_mint: Pubkey) -> Self {
+        Self {
+            enabled: u8::from(true),
+            cache_index: 0,
+            padding: [0; 6],
+            deposits: 0,
+            deposit_limit: u64::MAX,
+            token_supply: 0,
+            token_mint: Pubkey::default(),
+            high_water_mark: 0,
+            last_fee_accrual: 0,
+            lockup_period: 0,
+            lp_mint,
+        }
+    }
+
+    /// Whether deposits of this SPL Token are currently enabled or not.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    /// Enables or disables deposits of this SPL Token.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = u8::from(enabled);
+    }
+
     /// Calculate how many tokens should be minted for an LP position to be issued.
     /// We want to ensure that a LP's position always represents a proportion of the vault
     /// that is determined by the vault at the time of issue.
@@ -73,30 +531,146 @@ impl TokenInfo {
     /// Implying: issued tokens = (deposit *  existing tokens) / (old vault value)
     ///
     /// e.g. 10_000_000 * 90_000_000 / 90_000_000 = 10_000_000
-    pub fn calculate_mint_amount(&self, deposit_amount: u128) -> u128 {
-        if self.token_supply == 0 {
-            deposit_amount
-        } else {
-            (deposit_amount * self.token_supply as u128) / (self.deposits as u128)
-        }
+    ///
+    /// The old vault value is given by `net_assets`, the marked to market value of this SPL Token's
+    /// holdings in the [`Vault`]'s [`CypherSubAccount`], see [`Vault::get_token_net_assets`].
+    ///
+    /// Both the existing tokens and the vault value are offset by [`VIRTUAL_SHARES`] and [`VIRTUAL_ASSETS`],
+    /// which makes the first deposit mint [`VIRTUAL_SHARES`] LP tokens per native unit and a donation to the vault
+    /// unprofitable for the first depositor.
+    /// The result is rounded down, in favour of the vault.
+    pub fn calculate_mint_amount(&self, deposit_amount: u64, net_assets: u64) -> Result<u64> {
+        mul_div(
+            deposit_amount as u128,
+            self.token_supply as u128 + VIRTUAL_SHARES,
+            net_assets as u128 + VIRTUAL_ASSETS,
+            false,
+        )
     }
 
     /// Calculate how many tokens should be burned for a LP position to be redeemed.
     /// We want to ensure that a redeemer's position always represents a proportion of the position
     /// that is determined by the collateral at the time it is redeemed.
-    /// e.g. If the position is worth $100 and I want to redeem 10 tokens, I should get 10% of the collateral
+    /// e.g. If the position is worth $100 and I want to redeem $10, I should burn 10% of all minted tokens
     ///
-    /// To achieve this: (deposit value / old vault + deposit value) = (issued tokens / existing + issued tokens)
+    /// To achieve this: (redeem value / vault value) = (burned tokens / existing tokens)
+    ///
+    /// Implying: burn amount = (redeem amount * existing tokens) / vault value
+    ///
+    /// e.g. 10_000_000 * 100_000_000 / 100_000_000 = 10_000_000
+    ///
+    /// The vault value is given by `net_assets`, the marked to market value of this SPL Token's
+    /// holdings in the [`Vault`]'s [`CypherSubAccount`], see [`Vault::get_token_net_assets`].
+    ///
+    /// The result is rounded up, in favour of the vault.
+    pub fn calculate_burn_amount(&self, redeem_amount: u64, net_assets: u64) -> Result<u64> {
+        mul_div(
+            redeem_amount as u128,
+            self.token_supply as u128 + VIRTUAL_SHARES,
+            net_assets as u128 + VIRTUAL_ASSETS,
+            true,
+        )
+    }
+
+    /// Calculate how many tokens should be withdrawn for a given amount of LP tokens to be redeemed.
+    ///
+    /// Implying: redeem amount = (burn amount * vault value) / existing tokens
+    ///
+    /// The result is rounded down, in favour of the vault.
+    pub fn calculate_redeem_amount(&self, burn_amount: u64, net_assets: u64) -> Result<u64> {
+        mul_div(
+            burn_amount as u128,
+            net_assets as u128 + VIRTUAL_ASSETS,
+            self.token_supply as u128 + VIRTUAL_SHARES,
+            false,
+        )
+    }
+
+    /// Calculates the share price for the given net assets, scaled by [`SHARE_PRICE_SCALE`].
     ///
-    /// Implying: burn amount = (vault value * redeem amount) / existing tokens
+    /// Implying: share price = (vault value + virtual assets) / (existing tokens + virtual shares)
     ///
-    /// e.g. 100_000_000 * 10_000_000 / 100_000_000 = 10_000_000
-    pub fn calculate_burn_amount(&self, redeem_amount: u128) -> u128 {
-        (self.deposits as u128 * redeem_amount) / self.token_supply as u128
+    /// The virtual assets and shares are included as they are when pricing deposits and withdrawals.
+    pub fn calculate_share_price(&self, net_assets: u128) -> Result<u128> {
+        Ok(net_assets
+            .checked_add(VIRTUAL_ASSETS)
+            .and_then(|assets| assets.checked_mul(SHARE_PRICE_SCALE))
+            .ok_or(ErrorCode::MathOverflow)?
+            / (self.token_supply as u128 + VIRTUAL_SHARES))
+    }
+
+    /// Accrues the management and performance fees since the last accrual.
+    ///
+    /// The management fee accrues linearly over time on the net assets, while the performance fee
+    /// is only charged on the increase of the share price above the [`TokenInfo::high_water_mark`].
+    ///
+    /// The fees are paid by diluting the existing LPs, the returned amount of LP tokens should
+    /// be minted to the fee recipient such that it owns exactly the fee value out of the net assets.
+    ///
+    /// Implying: fee tokens = (fee value * existing tokens) / (vault value - fee value)
+    pub fn accrue_fees(
+        &mut self,
+        net_assets: u128,
+        management_fee_bps: u16,
+        performance_fee_bps: u16,
+        now: i64,
+    ) -> Result<u64> {
+        let last_fee_accrual = self.last_fee_accrual;
+        self.last_fee_accrual = now;
+
+        let share_price = self.calculate_share_price(net_assets)?;
+        if self.token_supply == 0 || last_fee_accrual == 0 {
+            self.high_water_mark = share_price.try_into().unwrap_or(u64::MAX);
+            return Ok(0);
+        }
+
+        let elapsed = now.saturating_sub(last_fee_accrual).max(0) as u128;
+        let management_fee = mul_div(
+            net_assets * management_fee_bps as u128,
+            elapsed,
+            BPS_DENOMINATOR * SECONDS_PER_YEAR,
+            false,
+        )? as u128;
+
+        let high_water_mark = self.high_water_mark as u128;
+        let performance_fee = if share_price > high_water_mark {
+            self.high_water_mark = share_price.try_into().unwrap_or(u64::MAX);
+            let gain = mul_div(
+                share_price - high_water_mark,
+                self.token_supply as u128,
+                SHARE_PRICE_SCALE,
+                false,
+            )?;
+            mul_div(
+                gain as u128,
+                performance_fee_bps as u128,
+                BPS_DENOMINATOR,
+                false,
+            )? as u128
+        } else {
+            0
+        };
+
+        let fee_value = management_fee + performance_fee;
+        if fee_value == 0 || fee_value >= net_assets {
+            return Ok(0);
+        }
+
+        let fee_amount = mul_div(
+            fee_value,
+            self.token_supply as u128,
+            net_assets - fee_value,
+            false,
+        )?;
+        self.token_supply = self
+            .token_supply
+            .checked_add(fee_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(fee_amount)
     }
 }
 
-#[account]
+#[account(zero_copy)]
 #[repr(C)]
 pub struct Vault {
     /// The version of the [`Vault`].
@@ -107,65 +681,1242 @@ pub struct Vault {
     pub account_number: u8, // 3
     /// The number of the [`cypher_client::CypherSubAccount`].
     pub sub_account_number: u8, // 4
-    /// The vault type.
-    pub vault_type: VaultType, // 5
-    padding: [u8; 3], // 8
+    /// The vault type, see [`Vault::vault_type`].
+    vault_type: u8, // 5
+    /// The pause state of the [`Vault`], see [`Vault::pause_state`].
+    pause_state: u8, // 6
+    padding: [u8; 2], // 8
     /// The [`Vault`]'s id.
     ///
     /// This is used as a seed for the [`Vault`]'s PDA.
     pub id: u64, // 16
 
-    /// The authority of the [`Vault`].
+    /// The creator of the [`Vault`].
     ///
-    /// This is used as a seed for the [`Vault`]'s PDA.
-    pub authority: Pubkey, // 48
-    padding2: [u64; 4], // 80
+    /// This is used as a seed for the [`Vault`]'s PDA and never changes.
+    pub creator: Pubkey, // 48
+    /// The authority of the [`Vault`], which administers its configuration.
+    ///
+    /// This starts out as the creator and can be transferred through [`Vault::pending_authority`].
+    pub authority: Pubkey, // 80
+
+    /// The recipient of the fees, which are paid in LP tokens.
+    pub fee_recipient: Pubkey, // 112
+    /// The annualized management fee, in basis points.
+    pub management_fee_bps: u16, // 114
+    /// The performance fee charged on gains above the high-water mark, in basis points.
+    pub performance_fee_bps: u16, // 116
+    padding3: [u8; 4], // 120
+    /// The maximum time, in seconds, a [`WithdrawTicket`] can wait before anyone can settle it.
+    pub max_withdraw_wait: i64, // 128
+    /// The vault-wide deposit limit, in native units of the quote token.
+    ///
+    /// A value of zero means there is no vault-wide deposit limit.
+    pub quote_deposit_limit: u64, // 136
+
+    /// The vault-wide LP token of a [`VaultType::MultiToken`] vault.
+    ///
+    /// Its amounts are denominated in native units of the quote token and it is
+    /// only in use once its LP token Mint has been created.
+    pub lp_info: TokenInfo, // 256
+
+    /// The manager of the [`Vault`].
+    ///
+    /// This is the trading delegate which can only perform trading and risk operations
+    /// through the [`Vault`]'s Cypher accounts, configuration stays with the authority.
+    pub manager: Pubkey, // 288
 
-    /// The tokens accepted in this [`Vault`].
-    pub token_infos: Vec<TokenInfo>,
+    /// The risk limits enforced after every trade.
+    pub risk_params: RiskParams, // 560
+
+    /// The authority proposed by the current authority, which takes over once it accepts.
+    ///
+    /// A default public key means there is no transfer in progress.
+    pub pending_authority: Pubkey, // 592
+
+    /// The guardian of the [`Vault`], set by the authority.
+    ///
+    /// The guardian can pause the [`Vault`] in an emergency but only the authority can unpause it.
+    pub guardian: Pubkey, // 624
+
+    /// The number of SPL Tokens accepted in this [`Vault`].
+    pub token_info_count: u8, // 625
+    padding4: [u8; 3], // 628
+    /// The number of [`WithdrawTicket`]s requested and not yet processed.
+    pub withdraw_ticket_count: u32, // 632
+
+    /// The number of the current epoch of an epoch [`Vault`].
+    pub epoch: u64, // 640
+    /// The timestamp at which the current epoch started.
+    pub epoch_start: i64, // 648
+    /// The duration of an epoch, in seconds.
+    ///
+    /// A value of zero means this is not an epoch [`Vault`], see [`Vault::is_epoch_mode`].
+    pub epoch_duration: i64, // 656
+    /// The amount of the SPL Token queued for deposit in the current epoch.
+    pub pending_deposits: u64, // 664
+    /// The amount of LP tokens queued for withdrawal in the current epoch.
+    pub pending_withdrawals: u64, // 672
+
+    /// The delay, in seconds, before a queued [`ConfigChange`] can be executed.
+    ///
+    /// A value of zero means the authority can also change the configuration directly.
+    pub config_timelock: i64, // 680
+    /// The number of [`PendingConfigChange`]s queued so far, used as the seed of the next one.
+    pub config_change_count: u64, // 688
+
+    /// The timestamp at which the [`Vault`] started winding down, see [`Vault::is_winding_down`].
+    pub wind_down_at: i64, // 696
+
+    /// The number of [`StrategySubAccount`]s of this [`Vault`].
+    pub strategy_sub_account_count: u8, // 697
+    padding5: [u8; 3], // 700
+    /// The number of [`EpochTicket`]s opened and not yet claimed or cancelled.
+    pub epoch_ticket_count: u32, // 704
+    /// The [`CypherSubAccount`]s added on top of the primary one, only the first
+    /// [`Vault::strategy_sub_account_count`] are in use.
+    strategy_sub_accounts: [StrategySubAccount; MAX_STRATEGY_SUB_ACCOUNTS], // 1952
+
+    /// The tokens accepted in this [`Vault`], only the first [`Vault::token_info_count`] are in use.
+    token_infos: [TokenInfo; MAX_TOKEN_INFOS], // 5792
 }
 
 impl Vault {
-    /// Calculates the size of the [`Vault`] for a given amount of SPL Tokens to be accepted.
-    pub fn compute_vault_size(token_info_count: usize) -> usize {
-        std::mem::size_of::<Vault>() + token_info_count * std::mem::size_of::<TokenInfo>()
-    }
+    /// The size of the [`Vault`] account, including the discriminator.
+    pub const LEN: usize = 8 + std::mem::size_of::<Vault>();
 
     /// Derives the address of a [`Vault`].
     #[cfg(feature = "client")]
-    pub fn derive_address(authority: &Pubkey, id: u64) -> (Pubkey, u8) {
+    pub fn derive_address(creator: &Pubkey, id: u64) -> (Pubkey, u8) {
         Pubkey::find_program_address(
-            &[VAULT_SEED, authority.as_ref(), id.to_le_bytes().as_ref()],
+            &[VAULT_SEED, creator.as_ref(), id.to_le_bytes().as_ref()],
             &crate::id(),
         )
     }
 
-    /// Derives the address of the LP token Mint for a given [`Vault`].
+    /// Derives the address of the vault-wide LP token Mint for a given [`Vault`].
+    #[cfg(feature = "client")]
+    pub fn derive_vault_lp_token_mint(vault: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[VAULT_LP_TOKEN_SEED, vault.as_ref()], &crate::id())
+    }
+
+    /// Derives the address of the LP token Mint for a given [`Vault`] and SPL Token Mint.
     #[cfg(feature = "client")]
     pub fn derive_lp_token_mint(vault: &Pubkey, token_mint: &Pubkey) -> (Pubkey, u8) {
-        Pubkey::find_program_address(&[LP_TOKEN_SEED, vault.as_ref()], &crate::id())
+        Pubkey::find_program_address(
+            &[LP_TOKEN_SEED, vault.as_ref(), token_mint.as_ref()],
+            &crate::id(),
+        )
     }
 
     /// Initialize the [`Vault`].
-    pub fn init(&mut self, authority: Pubkey, vault_bump: u8, args: &CreateVaultArgs) {
-        self.authority = authority;
+    pub fn init(&mut self, creator: Pubkey, vault_bump: u8, args: &CreateVaultArgs, now: i64) {
+        self.version = VAULT_VERSION;
+        self.creator = creator;
+        self.authority = creator;
         self.bump = vault_bump;
+        self.id = args.id;
+        self.vault_type = args.vault_type as u8;
         self.account_number = args.account_number;
         self.sub_account_number = args.sub_account_number;
-        self.token_infos = Vec::with_capacity(args.token_info_count);
+        self.fee_recipient = args.fee_recipient;
+        self.management_fee_bps = args.management_fee_bps;
+        self.performance_fee_bps = args.performance_fee_bps;
+        self.manager = args.manager;
+        self.max_withdraw_wait = args.max_withdraw_wait;
+        self.quote_deposit_limit = args.quote_deposit_limit;
+        self.epoch_duration = args.epoch_duration;
+        self.epoch_start = now;
+        self.config_timelock = args.config_timelock;
+    }
+
+    /// Initialize the [`Vault`] from a [`VaultV0`], the account data is expected to be zeroed.
+    ///
+    /// The original layout issued a single LP token per [`Vault`] at `lp_mint` and did not store its id,
+    /// the fees, risk limits and roles are initialized to their defaults with the authority as the manager.
+    pub fn init_from_v0(
+        &mut self,
+        v0: &VaultV0,
+        lp_mint: Pubkey,
+        args: &MigrateVaultArgs,
+        cache_indexes: &[u8],
+    ) -> Result<()> {
+        self.version = VAULT_VERSION;
+        self.bump = v0.bump;
+        self.account_number = v0.account_number;
+        self.sub_account_number = v0.sub_account_number;
+        self.vault_type = v0.vault_type as u8;
+        self.id = args.id;
+        self.creator = v0.authority;
+        self.authority = v0.authority;
+        self.fee_recipient = v0.authority;
+        self.manager = v0.authority;
+        for (ti, cache_index) in v0.token_infos.iter().zip(cache_indexes.iter()) {
+            self.add_token_info(TokenInfo {
+                enabled: u8::from(ti.enabled),
+                cache_index: *cache_index,
+                deposits: ti.deposits,
+                deposit_limit: ti.deposit_limit,
+                token_supply: ti.token_supply,
+                token_mint: ti.token_mint,
+                lp_mint,
+                ..Default::default()
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Gets the [`VaultType`] of the [`Vault`].
+    pub fn vault_type(&self) -> VaultType {
+        match self.vault_type {
+            1 => VaultType::MultiToken,
+            _ => VaultType::SingleToken,
+        }
+    }
+
+    /// Gets the [`PauseState`] of the [`Vault`].
+    pub fn pause_state(&self) -> PauseState {
+        match self.pause_state {
+            1 => PauseState::WithdrawOnly,
+            2 => PauseState::Paused,
+            _ => PauseState::Active,
+        }
+    }
+
+    /// Sets the [`PauseState`] of the [`Vault`].
+    pub fn set_pause_state(&mut self, pause_state: PauseState) {
+        self.pause_state = pause_state as u8;
+    }
+
+    /// Sets the fees of the [`Vault`].
+    ///
+    /// The fees owed at the current rates must have been accrued for every LP token up until now,
+    /// see [`Vault::fees_accrued_at`], so the new rates do not apply retroactively.
+    pub fn set_fees(&mut self, args: &SetFeesArgs, now: i64) -> Result<()> {
+        check!(self.fees_accrued_at(now), FeesNotAccrued);
+        self.fee_recipient = args.fee_recipient;
+        self.management_fee_bps = args.management_fee_bps;
+        self.performance_fee_bps = args.performance_fee_bps;
+        Ok(())
+    }
+
+    /// Whether the fees of every LP token with a supply have been accrued at the given timestamp.
+    pub fn fees_accrued_at(&self, now: i64) -> bool {
+        let is_accrued = |share_info: &TokenInfo| {
+            share_info.token_supply == 0 || share_info.last_fee_accrual == now
+        };
+        if self.has_vault_lp() {
+            is_accrued(&self.lp_info)
+        } else {
+            self.token_infos().iter().all(is_accrued)
+        }
+    }
+
+    /// Accrues the fees for a given SPL Token Mint, returning the amount of LP tokens owed to the fee recipient.
+    pub fn accrue_fees(&mut self, token_mint: Pubkey, net_assets: u64, now: i64) -> Result<u64> {
+        let management_fee_bps = self.management_fee_bps;
+        let performance_fee_bps = self.performance_fee_bps;
+        self.get_share_info_mut(token_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?
+            .accrue_fees(
+                net_assets as u128,
+                management_fee_bps,
+                performance_fee_bps,
+                now,
+            )
+    }
+
+    /// Gets the share price of the LP token issued for deposits of a given SPL Token Mint, scaled by [`SHARE_PRICE_SCALE`].
+    pub fn get_share_price(&self, token_mint: Pubkey, net_assets: u64) -> Result<u128> {
+        match self.get_share_info(token_mint) {
+            Some(share_info) => share_info.calculate_share_price(net_assets as u128),
+            None => Ok(SHARE_PRICE_SCALE * VIRTUAL_ASSETS / VIRTUAL_SHARES),
+        }
+    }
+
+    /// Whether the [`Vault`] has been migrated to the current [`VAULT_VERSION`].
+    pub fn is_migrated(&self) -> bool {
+        self.version == VAULT_VERSION
+    }
+
+    /// Whether the account holds a [`Vault`] migrated to the current [`VAULT_VERSION`].
+    ///
+    /// This is checked on the raw account data, a [`Vault`] in the original layout is shorter and can not be loaded,
+    /// so it has to come before any other constraint on the [`Vault`], which is why those do not use `has_one`.
+    pub fn is_migrated_account(account_info: &AccountInfo) -> bool {
+        account_info.data_len() >= Vault::LEN
+            && account_info
+                .try_borrow_data()
+                .map(|data| data[8] == VAULT_VERSION)
+                .unwrap_or(false)
+    }
+
+    /// Whether the authority can change the configuration directly instead of through a [`PendingConfigChange`].
+    pub fn allows_direct_config_changes(&self) -> bool {
+        self.config_timelock == 0
+    }
+
+    /// Applies a [`ConfigChange`] to the [`Vault`], which is expected to be validated.
+    pub fn apply_config_change(&mut self, change: &ConfigChange, now: i64) -> Result<()> {
+        match *change {
+            ConfigChange::DepositLimit {
+                token_mint,
+                deposit_limit,
+            } => {
+                let token_info = self
+                    .get_token_info_mut(token_mint)
+                    .ok_or(ErrorCode::InvalidTokenMint)?;
+                token_info.deposit_limit = deposit_limit;
+            }
+            ConfigChange::Fees { args } => self.set_fees(&args, now)?,
+            ConfigChange::Manager { manager } => self.manager = manager,
+            ConfigChange::Guardian { guardian } => self.guardian = guardian,
+            ConfigChange::PendingAuthority { pending_authority } => {
+                self.pending_authority = pending_authority
+            }
+            ConfigChange::ConfigTimelock { config_timelock } => {
+                self.config_timelock = config_timelock
+            }
+            ConfigChange::RiskParams {
+                sub_account_number,
+                risk_params,
+            } => {
+                *self
+                    .get_risk_params_mut(sub_account_number)
+                    .ok_or(ErrorCode::InvalidCypherAccount)? = risk_params;
+            }
+            ConfigChange::VaultDepositLimit {
+                quote_deposit_limit,
+            } => self.quote_deposit_limit = quote_deposit_limit,
+            ConfigChange::LockupPeriod {
+                token_mint,
+                lockup_period,
+            } => {
+                let token_info = self
+                    .get_token_info_mut(token_mint)
+                    .ok_or(ErrorCode::InvalidTokenMint)?;
+                token_info.lockup_period = lockup_period;
+            }
+            ConfigChange::MaxWithdrawWait { max_withdraw_wait } => {
+                self.max_withdraw_wait = max_withdraw_wait
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the [`StrategySubAccount`]s of this [`Vault`].
+    pub fn strategy_sub_accounts(&self) -> &[StrategySubAccount] {
+        &self.strategy_sub_accounts[..self.strategy_sub_account_count as usize]
+    }
+
+    /// Gets the [`StrategySubAccount`]s of this [`Vault`] as mutable.
+    pub fn strategy_sub_accounts_mut(&mut self) -> &mut [StrategySubAccount] {
+        &mut self.strategy_sub_accounts[..self.strategy_sub_account_count as usize]
+    }
+
+    /// Adds a [`StrategySubAccount`] to this [`Vault`].
+    pub fn add_strategy_sub_account(
+        &mut self,
+        strategy_sub_account: StrategySubAccount,
+    ) -> Result<()> {
+        let index = self.strategy_sub_account_count as usize;
+        check!(index < MAX_STRATEGY_SUB_ACCOUNTS, TooManySubAccounts);
+        self.strategy_sub_accounts[index] = strategy_sub_account;
+        self.strategy_sub_account_count += 1;
+        Ok(())
+    }
+
+    /// Gets the numbers of all of the [`Vault`]'s [`CypherSubAccount`]s, starting with the primary one.
+    pub fn sub_account_numbers(&self) -> Vec<u8> {
+        std::iter::once(self.sub_account_number)
+            .chain(
+                self.strategy_sub_accounts()
+                    .iter()
+                    .map(|ssa| ssa.sub_account_number),
+            )
+            .collect()
+    }
+
+    /// Gets the addresses of all of the [`Vault`]'s [`CypherSubAccount`]s, starting with the primary one.
+    pub fn get_sub_account_addresses(&self, vault: &Pubkey) -> Vec<Pubkey> {
+        let (cypher_account, _) = derive_account_address(vault, self.account_number);
+        self.sub_account_numbers()
+            .into_iter()
+            .map(|number| derive_sub_account_address(&cypher_account, number).0)
+            .collect()
+    }
+
+    /// Gets the risk limits of the [`CypherSubAccount`] at the given address.
+    pub fn get_risk_params(&self, vault: &Pubkey, sub_account: &Pubkey) -> Option<&RiskParams> {
+        let index = self
+            .get_sub_account_addresses(vault)
+            .iter()
+            .position(|address| address == sub_account)?;
+        match index {
+            0 => Some(&self.risk_params),
+            _ => Some(&self.strategy_sub_accounts()[index - 1].risk_params),
+        }
+    }
+
+    /// Gets the risk limits of the [`CypherSubAccount`] with the given number as mutable.
+    pub fn get_risk_params_mut(&mut self, sub_account_number: u8) -> Option<&mut RiskParams> {
+        if sub_account_number == self.sub_account_number {
+            return Some(&mut self.risk_params);
+        }
+        self.strategy_sub_accounts_mut()
+            .iter_mut()
+            .find(|ssa| ssa.sub_account_number == sub_account_number)
+            .map(|ssa| &mut ssa.risk_params)
+    }
+
+    /// Loads all of the [`Vault`]'s [`CypherSubAccount`]s, checking that they are the [`Vault`]'s.
+    ///
+    /// The [`StrategySubAccount`]s are expected as the first remaining accounts, in the order they were created.
+    pub fn load_sub_accounts<'info>(
+        &self,
+        vault: &Pubkey,
+        cypher_sub_account: &AccountLoader<'info, CypherSubAccount>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<Vec<AccountLoader<'info, CypherSubAccount>>> {
+        let addresses = self.get_sub_account_addresses(vault);
+        check!(
+            remaining_accounts.len() >= addresses.len() - 1,
+            InvalidStrategySubAccounts
+        );
+        check!(
+            cypher_sub_account.key() == addresses[0],
+            InvalidCypherAccount
+        );
+
+        let mut sub_accounts = vec![cypher_sub_account.clone()];
+        for (account_info, address) in remaining_accounts.iter().zip(addresses.iter().skip(1)) {
+            check!(account_info.key() == *address, InvalidStrategySubAccounts);
+            sub_accounts.push(AccountLoader::try_from(account_info)?);
+        }
+        Ok(sub_accounts)
+    }
+
+    /// Whether the [`Vault`] is winding down, which means it no longer accepts deposits and
+    /// its LPs can claim their pro-rata share of the remaining SPL Tokens once its positions are closed.
+    pub fn is_winding_down(&self) -> bool {
+        self.wind_down_at != 0
+    }
+
+    /// Whether the [`Vault`] has been winding down for longer than [`WIND_DOWN_SETTLEMENT_DELAY`].
+    ///
+    /// From then on anyone can close its positions and move its collateral back into the primary [`CypherSubAccount`],
+    /// so the LPs can claim their share through `claim_wind_down` even if the manager never does.
+    pub fn is_wind_down_overdue(&self, now: i64) -> bool {
+        self.is_winding_down()
+            && now >= self.wind_down_at.saturating_add(WIND_DOWN_SETTLEMENT_DELAY)
+    }
+
+    /// Whether the [`CypherSubAccount`] still has derivative positions or borrows open.
+    pub fn has_open_positions(
+        sub_account: &CypherSubAccount,
+        cache_account: &CacheAccount,
+    ) -> bool {
+        sub_account.positions.iter().any(|p| {
+            let derivative = &p.derivative;
+            let spot = &p.spot;
+            (derivative.market != Pubkey::default() && derivative.total_position() != I80F48::ZERO)
+                || (spot.token_mint != Pubkey::default()
+                    && spot
+                        .total_position(cache_account.get_price_cache(spot.cache_index as usize))
+                        < I80F48::ZERO)
+        })
+    }
+
+    /// Gets the size of every position of the [`CypherSubAccount`], as the absolute derivative position
+    /// and the absolute spot position of every SPL Token other than the quote token.
+    pub fn get_position_sizes(
+        sub_account: &CypherSubAccount,
+        cache_account: &CacheAccount,
+    ) -> Vec<(I80F48, I80F48)> {
+        sub_account
+            .positions
+            .iter()
+            .map(|p| {
+                let derivative = &p.derivative;
+                let spot = &p.spot;
+                let derivative_size = if derivative.market != Pubkey::default() {
+                    derivative.total_position().abs()
+                } else {
+                    I80F48::ZERO
+                };
+                let spot_size = if spot.token_mint != Pubkey::default()
+                    && spot.token_mint != quote_mint::ID
+                {
+                    spot.total_position(cache_account.get_price_cache(spot.cache_index as usize))
+                        .abs()
+                } else {
+                    I80F48::ZERO
+                };
+                (derivative_size, spot_size)
+            })
+            .collect()
+    }
+
+    /// Whether the [`CypherSubAccount`] has no positions or balances left, which is required to close it.
+    pub fn is_sub_account_empty(
+        sub_account: &CypherSubAccount,
+        cache_account: &CacheAccount,
+    ) -> bool {
+        sub_account.positions.iter().all(|p| {
+            let derivative = &p.derivative;
+            let spot = &p.spot;
+            (derivative.market == Pubkey::default() || derivative.total_position() == I80F48::ZERO)
+                && (spot.token_mint == Pubkey::default()
+                    || spot
+                        .total_position(cache_account.get_price_cache(spot.cache_index as usize))
+                        == I80F48::ZERO)
+        })
+    }
+
+    /// Calculates the amounts of each remaining SPL Token owed for `lp_amount` LP tokens of a given SPL Token Mint
+    /// when the [`Vault`] is winding down, in the order of the [`CypherSubAccount`]'s positions.
+    ///
+    /// The LP tokens are owed their pro-rata share of every spot position backing them, which is the entire
+    /// [`CypherSubAccount`] unless every SPL Token of a [`VaultType::MultiToken`] vault has its own LP token.
+    /// SPL Tokens with nothing owed are left out.
+    pub fn get_wind_down_claim_amounts(
+        &self,
+        token_mint: Pubkey,
+        lp_amount: u64,
+        sub_account: &CypherSubAccount,
+        cache_account: &CacheAccount,
+    ) -> Result<Vec<(Pubkey, u64)>> {
+        let share_info = self
+            .get_share_info(token_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let token_supply = share_info.token_supply;
+        check!(lp_amount <= token_supply, InvalidLpAmount);
+
+        let backs_all = self.has_vault_lp() || self.vault_type() == VaultType::SingleToken;
+        let mut claim_amounts = Vec::new();
+        for spot in sub_account.positions.iter().map(|p| &p.spot) {
+            if spot.token_mint == Pubkey::default() || !(backs_all || spot.token_mint == token_mint)
+            {
+                continue;
+            }
+            let cache = cache_account.get_price_cache(spot.cache_index as usize);
+            let balance: u64 = spot.total_position(cache).max(I80F48::ZERO).to_num();
+            let amount = mul_div(
+                balance as u128,
+                lp_amount as u128,
+                token_supply as u128,
+                false,
+            )?;
+            if amount != 0 {
+                claim_amounts.push((spot.token_mint, amount));
+            }
+        }
+        Ok(claim_amounts)
+    }
+
+    /// Records a wind-down claim of `lp_amount` LP tokens of a given SPL Token Mint along with the SPL Tokens withdrawn for it.
+    pub fn record_wind_down_claim(
+        &mut self,
+        token_mint: Pubkey,
+        lp_amount: u64,
+        claim_amounts: &[(Pubkey, u64)],
+    ) -> Result<()> {
+        for (claim_mint, amount) in claim_amounts.iter() {
+            if let Some(token_info) = self.get_token_info_mut(*claim_mint) {
+                token_info.deposits = token_info.deposits.saturating_sub(*amount);
+            }
+        }
+
+        let share_info = self
+            .get_share_info_mut(token_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let token_supply = share_info.token_supply;
+        share_info.token_supply = token_supply
+            .checked_sub(lp_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if self.has_vault_lp() {
+            let share_amount = mul_div(
+                self.lp_info.deposits as u128,
+                lp_amount as u128,
+                token_supply as u128,
+                false,
+            )?;
+            self.lp_info.deposits = self.lp_info.deposits.saturating_sub(share_amount);
+        }
+        Ok(())
+    }
+
+    /// Whether this is an epoch [`Vault`], which only accepts queued deposits and withdrawals.
+    pub fn is_epoch_mode(&self) -> bool {
+        self.epoch_duration != 0
+    }
+
+    /// Whether the current epoch has run its full duration and can be rolled.
+    pub fn is_epoch_over(&self, now: i64) -> bool {
+        now >= self.epoch_start.saturating_add(self.epoch_duration)
+    }
+
+    /// Starts the next epoch once the queued deposits and withdrawals have been processed.
+    pub fn start_next_epoch(&mut self, now: i64) -> Result<()> {
+        self.epoch = self.epoch.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        self.epoch_start = now;
+        self.pending_deposits = 0;
+        self.pending_withdrawals = 0;
+        Ok(())
+    }
+
+    /// Whether this [`Vault`] issues a single vault-wide LP token for all of its SPL Tokens.
+    pub fn has_vault_lp(&self) -> bool {
+        self.lp_info.lp_mint != Pubkey::default()
+    }
+
+    /// Gets the [`TokenInfo`] of the LP token issued for deposits of a given SPL Token Mint.
+    ///
+    /// This is the vault-wide [`Vault::lp_info`] if the [`Vault`] has one, otherwise it is the SPL Token's own.
+    pub fn get_share_info(&self, token_mint: Pubkey) -> Option<&TokenInfo> {
+        if self.has_vault_lp() {
+            Some(&self.lp_info)
+        } else {
+            self.get_token_info(token_mint)
+        }
+    }
+
+    /// Gets the [`TokenInfo`] of the LP token issued for deposits of a given SPL Token Mint.
+    pub fn get_share_info_mut(&mut self, token_mint: Pubkey) -> Option<&mut TokenInfo> {
+        if self.has_vault_lp() {
+            Some(&mut self.lp_info)
+        } else {
+            self.get_token_info_mut(token_mint)
+        }
+    }
+
+    /// Converts an amount of a given SPL Token into the units its LP token is priced in.
+    pub fn to_share_units(
+        &self,
+        token_info: &TokenInfo,
+        amount: u64,
+        cache_account: &CacheAccount,
+    ) -> Result<u64> {
+        if self.has_vault_lp() {
+            Self::get_quote_value(token_info, amount, cache_account)
+                .max(I80F48::ZERO)
+                .checked_to_num()
+                .ok_or_else(|| error!(ErrorCode::MathOverflow))
+        } else {
+            Ok(amount)
+        }
+    }
+
+    /// Converts an amount in the units a given SPL Token's LP token is priced in back into that SPL Token.
+    pub fn from_share_units(
+        &self,
+        token_info: &TokenInfo,
+        amount: u64,
+        cache_account: &CacheAccount,
+    ) -> Result<u64> {
+        if self.has_vault_lp() {
+            let cache = cache_account.get_price_cache(token_info.cache_index as usize);
+            I80F48::from_num(amount)
+                .checked_div(cache.oracle_price())
+                .unwrap_or(I80F48::ZERO)
+                .checked_to_num()
+                .ok_or_else(|| error!(ErrorCode::MathOverflow))
+        } else {
+            Ok(amount)
+        }
+    }
+
+    /// Records a deposit of a given SPL Token Mint along with the LP tokens minted for it.
+    pub fn record_deposit(
+        &mut self,
+        token_mint: Pubkey,
+        deposit_amount: u64,
+        share_amount: u64,
+        mint_amount: u64,
+    ) -> Result<()> {
+        let token_info = self
+            .get_token_info_mut(token_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        token_info.deposits = token_info
+            .deposits
+            .checked_add(deposit_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        if self.has_vault_lp() {
+            self.lp_info.deposits = self
+                .lp_info
+                .deposits
+                .checked_add(share_amount)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        let share_info = self
+            .get_share_info_mut(token_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        share_info.token_supply = share_info
+            .token_supply
+            .checked_add(mint_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Records a withdrawal of a given SPL Token Mint along with the LP tokens burned for it.
+    ///
+    /// The withdrawn amount may include PnL and interest on top of the deposited principal, so the principal
+    /// backing the LP token is reduced in proportion to the LP tokens burned, which keeps it at zero once the
+    /// LP token has no supply left and keeps the principal of a [`VaultType::MultiToken`] vault's SPL Tokens
+    /// in line with their LPs' share, see [`Vault::get_token_net_assets`].
+    pub fn record_withdraw(
+        &mut self,
+        token_mint: Pubkey,
+        withdraw_amount: u64,
+        burn_amount: u64,
+    ) -> Result<()> {
+        let has_vault_lp = self.has_vault_lp();
+        if has_vault_lp {
+            // the vault-wide LP token can be redeemed into any SPL Token, which only caps further deposits of it
+            let token_info = self
+                .get_token_info_mut(token_mint)
+                .ok_or(ErrorCode::InvalidTokenMint)?;
+            token_info.deposits = token_info.deposits.saturating_sub(withdraw_amount);
+        }
+
+        let share_info = self
+            .get_share_info_mut(token_mint)
+            .ok_or(ErrorCode::InvalidTokenMint)?;
+        let token_supply = share_info.token_supply;
+        check!(burn_amount <= token_supply, MathOverflow);
+        let principal = mul_div(
+            share_info.deposits as u128,
+            burn_amount as u128,
+            token_supply.max(1) as u128,
+            false,
+        )?;
+        share_info.deposits = if burn_amount == token_supply {
+            0
+        } else {
+            share_info.deposits.saturating_sub(principal)
+        };
+        share_info.token_supply = token_supply - burn_amount;
+        Ok(())
+    }
+
+    /// Whether any of the [`Vault`]'s LP tokens still has supply outstanding.
+    pub fn has_lp_supply(&self) -> bool {
+        self.lp_info.token_supply != 0 || self.token_infos().iter().any(|ti| ti.token_supply != 0)
+    }
+
+    /// Whether any deposit or withdrawal is still queued in the current epoch
+    /// or waiting in an [`EpochTicket`] or [`WithdrawTicket`] to be claimed.
+    pub fn has_unsettled_flows(&self) -> bool {
+        self.pending_deposits != 0
+            || self.pending_withdrawals != 0
+            || self.epoch_ticket_count != 0
+            || self.withdraw_ticket_count != 0
+    }
+
+    /// Gets the [`TokenInfo`]s of the SPL Tokens accepted in this [`Vault`].
+    pub fn token_infos(&self) -> &[TokenInfo] {
+        &self.token_infos[..self.token_info_count as usize]
+    }
+
+    /// Gets the [`TokenInfo`]s of the SPL Tokens accepted in this [`Vault`].
+    pub fn token_infos_mut(&mut self) -> &mut [TokenInfo] {
+        &mut self.token_infos[..self.token_info_count as usize]
+    }
+
+    /// Adds a [`TokenInfo`] for a newly accepted SPL Token.
+    pub fn add_token_info(&mut self, token_info: TokenInfo) -> Result<()> {
+        let index = self.token_info_count as usize;
+        check!(index < MAX_TOKEN_INFOS, TooManyTokens);
+        self.token_infos[index] = token_info;
+        self.token_info_count += 1;
+        Ok(())
     }
 
     /// Gets the [`TokenInfo`] for a given SPL Token Mint.
     pub fn get_token_info_mut(&mut self, token_mint: Pubkey) -> Option<&mut TokenInfo> {
-        self.token_infos
+        self.token_infos_mut()
             .iter_mut()
             .find(|ti| ti.token_mint == token_mint)
     }
 
     /// Gets the [`TokenInfo`] for a given SPL Token Mint.
     pub fn get_token_info(&self, token_mint: Pubkey) -> Option<&TokenInfo> {
-        self.token_infos
+        self.token_infos()
             .iter()
             .find(|ti| ti.token_mint == token_mint)
     }
+
+    /// Calculates the combined equity of the given [`CypherSubAccount`]s in native units of the quote token.
+    ///
+    /// Every spot position is valued at its oracle price with the deposit or borrow index applied,
+    /// so accrued interest is accounted for. Realized derivative PnL is settled by Cypher into the
+    /// quote token's spot position, the unrealized PnL of open derivative positions is marked to
+    /// their oracle price against their entry price.
+    pub fn get_sub_account_equity<S: Deref<Target = CypherSubAccount>>(
+        sub_accounts: &[S],
+        cache_account: &CacheAccount,
+    ) -> I80F48 {
+        sub_accounts
+            .iter()
+            .flat_map(|sa| sa.positions.iter())
+            .fold(I80F48::ZERO, |equity, p| {
+                let spot = &p.spot;
+                let spot_value = if spot.token_mint != Pubkey::default() {
+                    let cache = cache_account.get_price_cache(spot.cache_index as usize);
+                    spot.total_position(cache) * cache.oracle_price()
+                } else {
+                    I80F48::ZERO
+                };
+                let derivative = &p.derivative;
+                let unrealized_pnl = if derivative.market != Pubkey::default() {
+                    let cache = cache_account.get_price_cache(derivative.cache_index as usize);
+                    derivative.total_position() * (cache.oracle_price() - derivative.entry_price())
+                } else {
+                    I80F48::ZERO
+                };
+                equity + spot_value + unrealized_pnl
+            })
+    }
+
+    /// Calculates the value of an amount of a given SPL Token in native units of the quote token.
+    pub fn get_quote_value(
+        token_info: &TokenInfo,
+        amount: u64,
+        cache_account: &CacheAccount,
+    ) -> I80F48 {
+        let cache = cache_account.get_price_cache(token_info.cache_index as usize);
+        I80F48::from_num(amount) * cache.oracle_price()
+    }
+
+    /// Whether a deposit of the given value would exceed the vault-wide deposit limit.
+    pub fn exceeds_quote_deposit_limit<S: Deref<Target = CypherSubAccount>>(
+        &self,
+        deposit_value: I80F48,
+        sub_accounts: &[S],
+        cache_account: &CacheAccount,
+    ) -> bool {
+        if self.quote_deposit_limit == 0 {
+            return false;
+        }
+        let equity = Self::get_sub_account_equity(sub_accounts, cache_account).max(I80F48::ZERO);
+        equity + deposit_value > I80F48::from_num(self.quote_deposit_limit)
+    }
+
+    /// Calculates the marked to market value backing the LP token issued for a given SPL Token,
+    /// in the units that LP token is priced in.
+    ///
+    /// If the [`Vault`] has a vault-wide LP token this is the entire equity of its [`CypherSubAccount`]s.
+    pub fn get_share_net_assets<S: Deref<Target = CypherSubAccount>>(
+        &self,
+        token_mint: &Pubkey,
+        sub_accounts: &[S],
+        cache_account: &CacheAccount,
+    ) -> Result<u64> {
+        if self.has_vault_lp() {
+            Self::get_sub_account_equity(sub_accounts, cache_account)
+                .max(I80F48::ZERO)
+                .checked_to_num()
+                .ok_or_else(|| error!(ErrorCode::MathOverflow))
+        } else {
+            self.get_token_net_assets(token_mint, sub_accounts, cache_account)
+        }
+    }
+
+    /// Calculates the marked to market value of the [`Vault`]'s holdings backing the LP token of a given SPL Token,
+    /// in native units of that SPL Token.
+    ///
+    /// For a [`VaultType::SingleToken`] vault the entire equity of the [`CypherSubAccount`]s belongs
+    /// to the LPs of that SPL Token. For a [`VaultType::MultiToken`] vault the LPs of each SPL Token own
+    /// a share of the entire equity in proportion to the quote value of their principal, so trading PnL
+    /// and swaps between the SPL Tokens are shared by all LPs instead of moving value between them.
+    pub fn get_token_net_assets<S: Deref<Target = CypherSubAccount>>(
+        &self,
+        token_mint: &Pubkey,
+        sub_accounts: &[S],
+        cache_account: &CacheAccount,
+    ) -> Result<u64> {
+        let token_info = match self.get_token_info(*token_mint) {
+            Some(ti) => ti,
+            None => return Ok(0),
+        };
+        let equity = Self::get_sub_account_equity(sub_accounts, cache_account).max(I80F48::ZERO);
+
+        let net_assets = match self.vault_type() {
+            VaultType::SingleToken => {
+                let cache = cache_account.get_price_cache(token_info.cache_index as usize);
+                equity
+                    .checked_div(cache.oracle_price())
+                    .unwrap_or(I80F48::ZERO)
+            }
+            VaultType::MultiToken => {
+                let principal_value = self.token_infos().iter().fold(I80F48::ZERO, |total, ti| {
+                    total + Self::get_quote_value(ti, ti.deposits, cache_account)
+                });
+                match I80F48::from_num(token_info.deposits).checked_div(principal_value) {
+                    Some(share) => equity.checked_mul(share).ok_or(ErrorCode::MathOverflow)?,
+                    None => I80F48::ZERO,
+                }
+            }
+        };
+
+        net_assets
+            .max(I80F48::ZERO)
+            .checked_to_num()
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Gets the positive balance of an SPL Token in the [`CypherSubAccount`], in native units.
+    pub fn get_spot_balance(
+        token_mint: &Pubkey,
+        sub_account: &CypherSubAccount,
+        cache_account: &CacheAccount,
+    ) -> u64 {
+        sub_account
+            .positions
+            .iter()
+            .map(|p| &p.spot)
+            .find(|sp| sp.token_mint == *token_mint)
+            .map(|sp| {
+                let cache = cache_account.get_price_cache(sp.cache_index as usize);
+                sp.total_position(cache).max(I80F48::ZERO).to_num()
+            })
+            .unwrap_or(0)
+    }
+
+    /// Splits a withdrawal of a given SPL Token across the [`Vault`]'s [`CypherSubAccount`]s,
+    /// in the order given by [`Vault::load_sub_accounts`].
+    ///
+    /// Each sub account gives up to its balance of the SPL Token, anything the balances
+    /// do not cover is withdrawn from the primary sub account.
+    pub fn get_withdraw_amounts<S: Deref<Target = CypherSubAccount>>(
+        token_mint: &Pubkey,
+        amount: u64,
+        sub_accounts: &[S],
+        cache_account: &CacheAccount,
+    ) -> Vec<u64> {
+        let mut remaining = amount;
+        let mut withdraw_amounts: Vec<u64> = sub_accounts
+            .iter()
+            .map(|sa| {
+                let balance = Vault::get_spot_balance(token_mint, sa, cache_account);
+                let withdraw_amount = balance.min(remaining);
+                remaining -= withdraw_amount;
+                withdraw_amount
+            })
+            .collect();
+        if let Some(primary_amount) = withdraw_amounts.first_mut() {
+            *primary_amount += remaining;
+        }
+        withdraw_amounts
+    }
+}
+
+/// The original layout of a [`TokenInfo`], before [`VAULT_VERSION`] 1.
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize)]
+pub struct TokenInfoV0 {
+    pub enabled: bool,
+    padding: [u8; 7],
+    pub deposits: u64,
+    pub deposit_limit: u64,
+    pub token_supply: u64,
+    pub token_mint: Pubkey,
+}
+
+/// The original layout of a [`Vault`], before [`VAULT_VERSION`] 1.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct VaultV0 {
+    pub version: u8,
+    pub bump: u8,
+    pub account_number: u8,
+    pub sub_account_number: u8,
+    pub vault_type: VaultType,
+    padding: [u8; 3],
+    pub id: u64,
+    pub authority: Pubkey,
+    padding2: [u64; 4],
+    pub token_infos: Vec<TokenInfoV0>,
+}
+
+#[account]
+#[derive(Default)]
+pub struct WithdrawTicket {
+    /// The bump of the [`WithdrawTicket`].
+    pub bump: u8,
+    /// The [`Vault`] this withdrawal was requested from.
+    pub vault: Pubkey,
+    /// The owner of the LP tokens being redeemed.
+    pub owner: Pubkey,
+    /// The SPL Token Mint being withdrawn.
+    pub token_mint: Pubkey,
+    /// The amount of LP tokens locked in escrow.
+    pub lp_amount: u64,
+    /// The timestamp at which the withdrawal was requested.
+    pub requested_at: i64,
+    /// The minimum amount of the SPL Token the LP tokens have to be redeemed for when the ticket is processed.
+    pub min_tokens_out: u64,
+}
+
+impl WithdrawTicket {
+    /// The size of the [`WithdrawTicket`] account, including the discriminator.
+    pub const LEN: usize = 8 + std::mem::size_of::<WithdrawTicket>();
+
+    /// Initialize the [`WithdrawTicket`].
+    pub fn init(
+        &mut self,
+        vault: Pubkey,
+        owner: Pubkey,
+        token_mint: Pubkey,
+        lp_amount: u64,
+        min_tokens_out: u64,
+        bump: u8,
+        now: i64,
+    ) {
+        self.bump = bump;
+        self.vault = vault;
+        self.owner = owner;
+        self.token_mint = token_mint;
+        self.lp_amount = lp_amount;
+        self.min_tokens_out = min_tokens_out;
+        self.requested_at = now;
+    }
+
+    /// Whether this [`WithdrawTicket`] has waited long enough that anyone can settle it.
+    pub fn is_expired(&self, max_withdraw_wait: i64, now: i64) -> bool {
+        now >= self.requested_at.saturating_add(max_withdraw_wait)
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct DepositorPosition {
+    /// The bump of the [`DepositorPosition`].
+    pub bump: u8,
+    /// The [`Vault`] of this position.
+    pub vault: Pubkey,
+    /// The SPL Token Mint deposited.
+    pub token_mint: Pubkey,
+    /// The owner of this position.
+    pub owner: Pubkey,
+    /// The cumulative amount of the SPL Token deposited.
+    pub deposited_amount: u64,
+    /// The cumulative amount of LP tokens received.
+    pub lp_received: u64,
+    /// The cumulative amount of the SPL Token withdrawn.
+    pub withdrawn_amount: u64,
+    /// The cumulative amount of LP tokens redeemed.
+    pub lp_redeemed: u64,
+    /// The average share price at which the outstanding LP tokens were received.
+    ///
+    /// This is scaled by [`SHARE_PRICE_SCALE`].
+    pub average_entry_price: u64,
+    /// The timestamp of the last deposit that restarted the lockup period.
+    pub last_deposit_at: i64,
+    /// Whether the owner lets others deposit into this position through `deposit_for` while the SPL Token
+    /// has a lockup period, which restarts the lockup period of the position.
+    pub approves_deposits_for: bool,
+}
+
+impl DepositorPosition {
+    /// The size of the [`DepositorPosition`] account, including the discriminator.
+    pub const LEN: usize = 8 + std::mem::size_of::<DepositorPosition>();
+
+    /// Initialize the [`DepositorPosition`].
+    pub fn init(&mut self, vault: Pubkey, token_mint: Pubkey, owner: Pubkey, bump: u8) {
+        self.bump = bump;
+        self.vault = vault;
+        self.token_mint = token_mint;
+        self.owner = owner;
+    }
+
+    /// The amount of LP tokens received and not yet redeemed.
+    pub fn lp_outstanding(&self) -> u64 {
+        self.lp_received.saturating_sub(self.lp_redeemed)
+    }
+
+    /// Records a deposit, updating the average entry share price.
+    ///
+    /// The `share_amount` is the deposit amount in the units the LP token is priced in.
+    pub fn record_deposit(&mut self, deposit_amount: u64, share_amount: u64, lp_amount: u64) {
+        if lp_amount != 0 {
+            let lp_outstanding = self.lp_outstanding() as u128;
+            let entry_price = share_amount as u128 * SHARE_PRICE_SCALE / lp_amount as u128;
+            let average_entry_price = (self.average_entry_price as u128 * lp_outstanding
+                + entry_price * lp_amount as u128)
+                / (lp_outstanding + lp_amount as u128);
+            self.average_entry_price = average_entry_price.try_into().unwrap_or(u64::MAX);
+        }
+        self.deposited_amount = self.deposited_amount.saturating_add(deposit_amount);
+        self.lp_received = self.lp_received.saturating_add(lp_amount);
+    }
+
+    /// Restarts the lockup period of the LP tokens in this position's lockup escrow.
+    pub fn restart_lockup(&mut self, now: i64) {
+        self.last_deposit_at = now;
+    }
+
+    /// Records a withdrawal.
+    pub fn record_withdraw(&mut self, withdraw_amount: u64, lp_amount: u64) {
+        self.withdrawn_amount = self.withdrawn_amount.saturating_add(withdraw_amount);
+        self.lp_redeemed = self.lp_redeemed.saturating_add(lp_amount);
+    }
+
+    /// Whether the LP tokens in this position's lockup escrow are still locked.
+    pub fn is_locked(&self, lockup_period: i64, now: i64) -> bool {
+        now < self.last_deposit_at.saturating_add(lockup_period)
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct EpochTicket {
+    /// The bump of the [`EpochTicket`].
+    pub bump: u8,
+    /// The epoch [`Vault`] the deposit and withdrawal were queued in.
+    pub vault: Pubkey,
+    /// The owner of the queued deposit and withdrawal.
+    pub owner: Pubkey,
+    /// The epoch the deposit and withdrawal were queued in.
+    pub epoch: u64,
+    /// The amount of the SPL Token queued for deposit.
+    pub deposit_amount: u64,
+    /// The amount of LP tokens queued for withdrawal.
+    pub lp_amount: u64,
+}
+
+impl EpochTicket {
+    /// The size of the [`EpochTicket`] account, including the discriminator.
+    pub const LEN: usize = 8 + std::mem::size_of::<EpochTicket>();
+
+    /// Initialize the [`EpochTicket`].
+    pub fn init(&mut self, vault: Pubkey, owner: Pubkey, bump: u8) {
+        self.bump = bump;
+        self.vault = vault;
+        self.owner = owner;
+    }
+
+    /// Whether anything can be queued in the given epoch with this [`EpochTicket`].
+    ///
+    /// A ticket only tracks a single epoch, what was queued in an earlier epoch has to be claimed first.
+    pub fn can_queue(&self, epoch: u64) -> bool {
+        self.epoch == epoch || (self.deposit_amount == 0 && self.lp_amount == 0)
+    }
+
+    /// Queues a deposit in the given epoch.
+    pub fn queue_deposit(&mut self, epoch: u64, amount: u64) -> Result<()> {
+        self.epoch = epoch;
+        self.deposit_amount = self
+            .deposit_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Queues a withdrawal in the given epoch.
+    pub fn queue_withdraw(&mut self, epoch: u64, lp_amount: u64) -> Result<()> {
+        self.epoch = epoch;
+        self.lp_amount = self
+            .lp_amount
+            .checked_add(lp_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct EpochRecord {
+    /// The epoch [`Vault`] of this record.
+    pub vault: Pubkey,
+    /// The epoch that was rolled.
+    pub epoch: u64,
+    /// The total amount of the SPL Token deposited in this epoch.
+    pub deposits: u64,
+    /// The total amount of LP tokens minted for the deposits.
+    pub lp_minted: u64,
+    /// The total amount of LP tokens burned for the withdrawals.
+    pub lp_burned: u64,
+    /// The total amount of the SPL Token withdrawn for the burned LP tokens.
+    pub withdrawn: u64,
+    /// The share price all deposits and withdrawals of this epoch were processed at.
+    ///
+    /// This is scaled by [`SHARE_PRICE_SCALE`].
+    pub share_price: u128,
+    /// The timestamp at which the epoch was rolled.
+    pub rolled_at: i64,
+}
+
+impl EpochRecord {
+    /// The size of the [`EpochRecord`] account, including the discriminator.
+    pub const LEN: usize = 8 + std::mem::size_of::<EpochRecord>();
+
+    /// Calculates the LP tokens owed for a deposit queued in this epoch, their pro-rata share of [`EpochRecord::lp_minted`].
+    pub fn calculate_claim_lp_amount(&self, deposit_amount: u64) -> Result<u64> {
+        if self.deposits == 0 {
+            return Ok(0);
+        }
+        mul_div(
+            deposit_amount as u128,
+            self.lp_minted as u128,
+            self.deposits as u128,
+            false,
+        )
+    }
+
+    /// Calculates the SPL Tokens owed for a withdrawal queued in this epoch, their pro-rata share of [`EpochRecord::withdrawn`].
+    pub fn calculate_claim_token_amount(&self, lp_amount: u64) -> Result<u64> {
+        if self.lp_burned == 0 {
+            return Ok(0);
+        }
+        mul_div(
+            lp_amount as u128,
+            self.withdrawn as u128,
+            self.lp_burned as u128,
+            false,
+        )
+    }
+}
+
+#[account]
+pub struct PendingConfigChange {
+    /// The bump of the [`PendingConfigChange`].
+    pub bump: u8,
+    /// The [`Vault`] the change applies to.
+    pub vault: Pubkey,
+    /// The number of this change, see [`Vault::config_change_count`].
+    pub id: u64,
+    /// The queued change.
+    pub change: ConfigChange,
+    /// The timestamp at which the change was queued.
+    pub queued_at: i64,
+    /// The earliest timestamp at which the change can be executed.
+    pub executable_at: i64,
+}
+
+impl PendingConfigChange {
+    /// The size of the [`PendingConfigChange`] account, including the discriminator.
+    pub const LEN: usize = 8 + std::mem::size_of::<PendingConfigChange>();
+
+    /// Initialize the [`PendingConfigChange`].
+    pub fn init(
+        &mut self,
+        vault: Pubkey,
+        id: u64,
+        change: ConfigChange,
+        bump: u8,
+        now: i64,
+        config_timelock: i64,
+    ) {
+        self.bump = bump;
+        self.vault = vault;
+        self.id = id;
+        self.change = change;
+        self.queued_at = now;
+        self.executable_at = now.saturating_add(config_timelock);
+    }
+
+    /// Whether the timelock of this change has passed.
+    pub fn is_executable(&self, now: i64) -> bool {
+        now >= self.executable_at
+    }
 }
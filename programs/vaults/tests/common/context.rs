@@ -1,4 +1,7 @@
-use anchor_lang::{AccountDeserialize, AnchorSerialize, Owner, ToAccountMetas, ZeroCopy};
+use anchor_lang::{
+    AccountDeserialize, AccountSerialize, AnchorDeserialize, AnchorSerialize, Discriminator, Event,
+    Owner, ToAccountMetas, ZeroCopy,
+};
 use anchor_spl::token::spl_token;
 use anchor_spl::{associated_token, token::spl_token::native_mint};
 use arrayref::array_ref;
@@ -7,7 +10,7 @@ use bytemuck::Pod;
 use bytemuck::{bytes_of, from_bytes};
 use cypher_client::utils::derive_public_clearing_address;
 use cypher_client::{quote_mint, Cache, ClearingConfig};
-use cypher_client::{CacheAccount, ClearingType};
+use cypher_client::{CacheAccount, ClearingType, Pool};
 use cypher_client::{Clearing, FeeTier};
 use fixed::types::I80F48;
 use solana_program::{
@@ -46,6 +49,9 @@ pub struct ProgramTestContext {
     pub clearing_auth: Keypair,
     pub clearing: Pubkey,
     pub cache: Pubkey,
+
+    /// The log messages of the last processed transaction.
+    pub last_logs: Vec<String>,
 }
 
 impl ProgramTestContext {
@@ -75,6 +81,7 @@ impl ProgramTestContext {
             clearing_auth: Keypair::new(),
             clearing: clearing.0,
             cache: Pubkey::default(),
+            last_logs: Vec::new(),
         };
 
         // add native mint
@@ -193,10 +200,28 @@ impl ProgramTestContext {
 
         transaction.sign(&all_signers, self.context.last_blockhash);
 
-        self.context
+        let result = self
+            .context
             .banks_client
-            .process_transaction(transaction)
-            .await
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        self.last_logs = result
+            .metadata
+            .map(|metadata| metadata.log_messages)
+            .unwrap_or_default();
+        result.result.map_err(BanksClientError::TransactionError)
+    }
+
+    /// Gets the events of type `T` emitted by the last processed transaction.
+    #[allow(dead_code)]
+    pub fn get_events<T: Event>(&self) -> Vec<T> {
+        self.last_logs
+            .iter()
+            .filter_map(|log| log.strip_prefix("Program data: "))
+            .filter_map(|data| base64::decode(data).ok())
+            .filter(|data| data.len() >= 8 && data[..8] == T::discriminator())
+            .map(|data| T::try_from_slice(&data[8..]).unwrap())
+            .collect()
     }
 
     #[allow(dead_code)]
@@ -241,6 +266,63 @@ impl ProgramTestContext {
         self.context.set_account(pubkey, &account.into());
     }
 
+    /// Adds an account holding the given raw data.
+    #[allow(dead_code)]
+    pub fn add_raw_account(&mut self, pubkey: &Pubkey, data: Vec<u8>, owner: &Pubkey) {
+        let mut account = Account::new(u32::MAX as u64, 0, owner);
+        account.data = data;
+        self.context.set_account(pubkey, &account.into());
+    }
+
+    /// Adds an Anchor account of `len` bytes, including its discriminator.
+    #[allow(dead_code)]
+    pub fn add_anchor_account<T: AccountSerialize + Owner>(
+        &mut self,
+        pubkey: &Pubkey,
+        data: &T,
+        len: usize,
+    ) {
+        let mut account = Account::new(u32::MAX as u64, len, &T::owner());
+        let mut writer: &mut [u8] = &mut account.data;
+        data.try_serialize(&mut writer).unwrap();
+        self.context.set_account(pubkey, &account.into());
+    }
+
+    /// Adds an SPL Token account holding `amount` of the given SPL Token Mint.
+    #[allow(dead_code)]
+    pub fn add_token_account(
+        &mut self,
+        pubkey: &Pubkey,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) {
+        self.add_packable_account(
+            pubkey,
+            u32::MAX as u64,
+            &spl_token::state::Account {
+                mint: *mint,
+                owner: *owner,
+                amount,
+                state: AccountState::Initialized,
+                ..Default::default()
+            },
+            &spl_token::id(),
+        );
+    }
+
+    /// Adds a Cypher [`Pool`] for the given SPL Token Mint, priced with the first price cache.
+    #[allow(dead_code)]
+    pub fn add_pool(&mut self, token_mint: &Pubkey) -> Pubkey {
+        let mut pool: Box<Pool> = Box::new(bytemuck::Zeroable::zeroed());
+        pool.token_mint = *token_mint;
+        pool.config.cache_index = 0;
+
+        let address = Pubkey::new_unique();
+        self.add_zero_copy_account(&address, pool, &cypher_client::id());
+        address
+    }
+
     #[allow(dead_code)]
     pub async fn get_zero_copy_account<T: ZeroCopy + Owner>(&mut self, address: Pubkey) -> Box<T> {
         let account = self
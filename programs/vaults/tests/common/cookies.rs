@@ -1,11 +1,22 @@
-use anchor_lang::{InstructionData, ToAccountMetas};
-use anchor_spl::token;
-use cypher_client::utils::{derive_account_address, derive_sub_account_address};
-use solana_program_test::{processor, BanksClientError};
+use anchor_lang::{Discriminator, InstructionData, ToAccountMetas};
+use anchor_spl::token::{self, spl_token};
+use cypher_client::{
+    utils::{derive_account_address, derive_sub_account_address},
+    PoolNode,
+};
+use solana_program::program_option::COption;
+use solana_program_test::BanksClientError;
 use solana_sdk::{
-    pubkey::Pubkey, rent, signature::Keypair, signer::Signer, system_program, sysvar::SysvarId,
+    instruction::InstructionError, pubkey::Pubkey, rent, signature::Keypair, signer::Signer,
+    system_program, sysvar::SysvarId, transaction::TransactionError,
+};
+use vaults::{
+    ConfigChange, CreateStrategySubAccountArgs, CreateVaultArgs, EpochTicket, MarketType,
+    MigrateVaultArgs, OpenDepositsArgs, PendingConfigChange, RiskParams, Vault, VaultType,
+    CONFIG_CHANGE_SEED, DEPOSITOR_POSITION_SEED, EPOCH_LP_ESCROW_SEED, EPOCH_RECORD_SEED,
+    EPOCH_TICKET_SEED, EPOCH_TOKEN_ESCROW_SEED, LOCKUP_ESCROW_SEED, LP_TOKEN_SEED,
+    VAULT_LP_TOKEN_SEED, VAULT_SEED, WITHDRAW_ESCROW_SEED, WITHDRAW_TICKET_SEED,
 };
-use vaults::{CreateVaultArgs, OpenDepositsArgs, Vault, LP_TOKEN_SEED, VAULT_SEED};
 
 use super::{ProgramTestContext, ProgramTestContextConfig};
 
@@ -24,20 +35,47 @@ impl UserCookie {
 pub struct VaultCookie {
     pub authority: Keypair,
     pub address: Pubkey,
+    pub cypher_account: Pubkey,
+    pub cypher_sub_account: Pubkey,
 }
 
 impl VaultCookie {
+    #[allow(dead_code)]
     pub async fn create_vault(
         test: &mut ProgramTestContext,
         id: u64,
+    ) -> Result<VaultCookie, BanksClientError> {
+        VaultCookie::create_vault_with_timelock(test, id, 0).await
+    }
+
+    #[allow(dead_code)]
+    pub async fn create_vault_with_timelock(
+        test: &mut ProgramTestContext,
+        id: u64,
+        config_timelock: i64,
+    ) -> Result<VaultCookie, BanksClientError> {
+        VaultCookie::create_vault_with_args(test, id, |args| {
+            args.config_timelock = config_timelock;
+        })
+        .await
+    }
+
+    /// Creates a [`VaultType::SingleToken`] vault, with the arguments that do not depend on its accounts set by `f`.
+    #[allow(dead_code)]
+    pub async fn create_vault_with_args(
+        test: &mut ProgramTestContext,
+        id: u64,
+        f: impl FnOnce(&mut CreateVaultArgs),
     ) -> Result<VaultCookie, BanksClientError> {
         let authority = Keypair::new();
-        let (cypher_account, cypher_account_bump) = derive_account_address(&authority.pubkey(), 0);
+        test.add_account(&authority.pubkey());
+
+        // the vault is the authority of its cypher accounts
+        let (vault, _) = VaultCookie::derive_vault_address(&authority.pubkey(), id);
+        let (cypher_account, cypher_account_bump) = derive_account_address(&vault, 0);
         let (cypher_sub_account, cypher_sub_account_bump) =
             derive_sub_account_address(&cypher_account, 0);
 
-        let (vault, vault_bump) = VaultCookie::derive_vault_address(&authority.pubkey(), id);
-
         let accounts = vaults::accounts::CreateVault {
             vault,
             clearing: test.clearing,
@@ -50,18 +88,26 @@ impl VaultCookie {
             rent: rent::Rent::id(),
         };
 
-        let ix_data = vaults::instruction::CreateVault {
-            args: CreateVaultArgs {
-                id,
-                account_number: 0,
-                account_bump: cypher_account_bump,
-                sub_account_number: 0,
-                sub_account_bump: cypher_sub_account_bump,
-                sub_account_alias: [0; 32],
-                token_info_count: 1,
-            },
-        }
-        .data();
+        let mut args = CreateVaultArgs {
+            id,
+            vault_type: VaultType::SingleToken,
+            account_number: 0,
+            account_bump: cypher_account_bump,
+            sub_account_number: 0,
+            sub_account_bump: cypher_sub_account_bump,
+            sub_account_alias: [0; 32],
+            management_fee_bps: 0,
+            performance_fee_bps: 0,
+            fee_recipient: authority.pubkey(),
+            manager: authority.pubkey(),
+            max_withdraw_wait: 0,
+            quote_deposit_limit: 0,
+            epoch_duration: 0,
+            config_timelock: 0,
+        };
+        f(&mut args);
+
+        let ix_data = vaults::instruction::CreateVault { args }.data();
 
         test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&authority]))
             .await?;
@@ -69,19 +115,34 @@ impl VaultCookie {
         Ok(VaultCookie {
             authority,
             address: vault,
+            cypher_account,
+            cypher_sub_account,
         })
     }
 
+    #[allow(dead_code)]
     pub async fn open_deposits(
         &self,
         test: &mut ProgramTestContext,
         token_mint: Pubkey,
     ) -> Result<(), BanksClientError> {
-        let (lp_token_mint, _) = VaultCookie::derive_lp_mint_address(&self.address, &token_mint);
+        self.open_deposits_with_lockup(test, token_mint, 0).await
+    }
+
+    #[allow(dead_code)]
+    pub async fn open_deposits_with_lockup(
+        &self,
+        test: &mut ProgramTestContext,
+        token_mint: Pubkey,
+        lockup_period: i64,
+    ) -> Result<(), BanksClientError> {
+        let lp_token_mint = self.lp_token_mint(&token_mint);
+        let pool = test.add_pool(&token_mint);
 
         let accounts = vaults::accounts::OpenDeposits {
             vault: self.address,
             lp_token_mint,
+            pool,
             authority: self.authority.pubkey(),
             payer: self.authority.pubkey(),
             system_program: system_program::id(),
@@ -94,6 +155,8 @@ impl VaultCookie {
                 token_mint,
                 deposit_limit: u64::MAX,
                 decimals: 0,
+                lockup_period,
+                cache_index: 0,
             },
         }
         .data();
@@ -104,6 +167,1055 @@ impl VaultCookie {
         Ok(())
     }
 
+    #[allow(dead_code)]
+    pub async fn disable_deposits(
+        &self,
+        test: &mut ProgramTestContext,
+        token_mint: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::DisableDeposits {
+            vault: self.address,
+            authority: self.authority.pubkey(),
+        };
+
+        let ix_data = vaults::instruction::DisableDeposits { token_mint }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    /// Deposits `amount` of the SPL Token from a new, empty token account of the user.
+    ///
+    /// The Cypher pool accounts are placeholders, so only deposits that are rejected before reaching Cypher can be tested.
+    #[allow(dead_code)]
+    pub async fn deposit(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        amount: u64,
+        min_lp_out: u64,
+    ) -> Result<(), BanksClientError> {
+        let owner = user.keypair.pubkey();
+        let vault = test.get_zero_copy_account::<Vault>(self.address).await;
+        let lp_mint = self.lp_token_mint(&token_mint);
+        let lp_token_account = test.create_token_account(&owner, &lp_mint).await;
+        let fee_token_account = test
+            .create_token_account(&vault.fee_recipient, &lp_mint)
+            .await;
+        let source_token_account = test.create_token_account(&owner, &token_mint).await;
+        let (pool, pool_node, token_vault) = add_pool_accounts(test, &token_mint).await;
+        let depositor_position = self.depositor_position(&token_mint, &owner).0;
+
+        let accounts = vaults::accounts::Deposit {
+            vault: self.address,
+            lp_mint,
+            lp_token_account,
+            fee_token_account,
+            depositor_position,
+            lockup_escrow_token_account: self.lockup_escrow(&depositor_position),
+            cache_account: test.cache,
+            clearing: test.clearing,
+            cypher_account: self.cypher_account,
+            cypher_sub_account: self.cypher_sub_account,
+            pool,
+            pool_node,
+            token_vault,
+            source_token_account,
+            token_mint,
+            authority: owner,
+            payer: owner,
+            system_program: system_program::id(),
+            token_program: token::ID,
+            cypher_program: cypher_client::id(),
+            rent: rent::Rent::id(),
+        };
+
+        let ix_data = vaults::instruction::Deposit { amount, min_lp_out }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&user.keypair]))
+            .await
+    }
+
+    /// Deposits `amount` of the SPL Token funded by the user on behalf of `beneficiary`, see [`VaultCookie::deposit`].
+    #[allow(dead_code)]
+    pub async fn deposit_for(
+        &self,
+        test: &mut ProgramTestContext,
+        funder: &UserCookie,
+        beneficiary: Pubkey,
+        token_mint: Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let funder_pk = funder.keypair.pubkey();
+        let vault = test.get_zero_copy_account::<Vault>(self.address).await;
+        let lp_mint = self.lp_token_mint(&token_mint);
+        let lp_token_account = test.create_token_account(&beneficiary, &lp_mint).await;
+        let fee_token_account = test
+            .create_token_account(&vault.fee_recipient, &lp_mint)
+            .await;
+        let source_token_account = test.create_token_account(&funder_pk, &token_mint).await;
+        let (pool, pool_node, token_vault) = add_pool_accounts(test, &token_mint).await;
+        let depositor_position = self.depositor_position(&token_mint, &beneficiary).0;
+
+        let accounts = vaults::accounts::DepositFor {
+            vault: self.address,
+            lp_mint,
+            lp_token_account,
+            fee_token_account,
+            depositor_position,
+            lockup_escrow_token_account: self.lockup_escrow(&depositor_position),
+            cache_account: test.cache,
+            clearing: test.clearing,
+            cypher_account: self.cypher_account,
+            cypher_sub_account: self.cypher_sub_account,
+            pool,
+            pool_node,
+            token_vault,
+            source_token_account,
+            token_mint,
+            funder: funder_pk,
+            payer: funder_pk,
+            system_program: system_program::id(),
+            token_program: token::ID,
+            cypher_program: cypher_client::id(),
+            rent: rent::Rent::id(),
+        };
+
+        let ix_data = vaults::instruction::DepositFor {
+            beneficiary,
+            amount,
+            min_lp_out: 0,
+        }
+        .data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&funder.keypair]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn approve_deposits_for(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        approved: bool,
+    ) -> Result<(), BanksClientError> {
+        let owner = user.keypair.pubkey();
+
+        let accounts = vaults::accounts::ApproveDepositsFor {
+            vault: self.address,
+            depositor_position: self.depositor_position(&token_mint, &owner).0,
+            token_mint,
+            owner,
+            payer: owner,
+            system_program: system_program::id(),
+        };
+
+        let ix_data = vaults::instruction::ApproveDepositsFor { approved }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&user.keypair]))
+            .await
+    }
+
+    /// Withdraws `amount` of the SPL Token into a new token account of the user, see [`VaultCookie::deposit`].
+    #[allow(dead_code)]
+    pub async fn withdraw(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        amount: u64,
+        max_lp_burn: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix_data = vaults::instruction::Withdraw {
+            amount,
+            max_lp_burn,
+        }
+        .data();
+        self.send_withdraw_ix(test, user, token_mint, ix_data).await
+    }
+
+    /// Redeems exactly `lp_amount` LP tokens of the SPL Token, see [`VaultCookie::deposit`].
+    #[allow(dead_code)]
+    pub async fn redeem(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        lp_amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix_data = vaults::instruction::Redeem {
+            lp_amount,
+            min_tokens_out,
+        }
+        .data();
+        self.send_withdraw_ix(test, user, token_mint, ix_data).await
+    }
+
+    /// Sends an instruction taking the [`vaults::accounts::Withdraw`] accounts of the user.
+    async fn send_withdraw_ix(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        ix_data: Vec<u8>,
+    ) -> Result<(), BanksClientError> {
+        let owner = user.keypair.pubkey();
+        let vault = test.get_zero_copy_account::<Vault>(self.address).await;
+        let lp_mint = self.lp_token_mint(&token_mint);
+        let lp_token_account = test.create_token_account(&owner, &lp_mint).await;
+        let fee_token_account = test
+            .create_token_account(&vault.fee_recipient, &lp_mint)
+            .await;
+        let destination_token_account = test.create_token_account(&owner, &token_mint).await;
+        let (pool, pool_node, token_vault) = add_pool_accounts(test, &token_mint).await;
+
+        let accounts = vaults::accounts::Withdraw {
+            vault: self.address,
+            lp_mint,
+            lp_token_account,
+            fee_token_account,
+            depositor_position: self.depositor_position(&token_mint, &owner).0,
+            cache_account: test.cache,
+            clearing: test.clearing,
+            cypher_account: self.cypher_account,
+            cypher_sub_account: self.cypher_sub_account,
+            pool,
+            pool_node,
+            token_vault,
+            destination_token_account,
+            token_mint,
+            vault_signer: Pubkey::new_unique(),
+            authority: owner,
+            payer: owner,
+            system_program: system_program::id(),
+            token_program: token::ID,
+            cypher_program: cypher_client::id(),
+        };
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&user.keypair]))
+            .await
+    }
+
+    /// Queues a [`ConfigChange`], returning the address of its [`PendingConfigChange`].
+    #[allow(dead_code)]
+    pub async fn queue_config_change(
+        &self,
+        test: &mut ProgramTestContext,
+        change: ConfigChange,
+    ) -> Result<Pubkey, BanksClientError> {
+        let vault = test.get_zero_copy_account::<Vault>(self.address).await;
+        let (pending_config_change, _) = Pubkey::find_program_address(
+            &[
+                CONFIG_CHANGE_SEED,
+                self.address.as_ref(),
+                vault.config_change_count.to_le_bytes().as_ref(),
+            ],
+            &vaults::id(),
+        );
+
+        let accounts = vaults::accounts::QueueConfigChange {
+            vault: self.address,
+            pending_config_change,
+            clearing: test.clearing,
+            authority: self.authority.pubkey(),
+            payer: self.authority.pubkey(),
+            system_program: system_program::id(),
+        };
+
+        let ix_data = vaults::instruction::QueueConfigChange { change }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await?;
+
+        Ok(pending_config_change)
+    }
+
+    #[allow(dead_code)]
+    pub async fn execute_config_change(
+        &self,
+        test: &mut ProgramTestContext,
+        pending_config_change: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::ExecuteConfigChange {
+            vault: self.address,
+            pending_config_change,
+            clearing: test.clearing,
+            authority: self.authority.pubkey(),
+        };
+
+        let ix_data = vaults::instruction::ExecuteConfigChange {}.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_pending_config_change(
+        &self,
+        test: &mut ProgramTestContext,
+        pending_config_change: Pubkey,
+    ) -> PendingConfigChange {
+        test.load_anchor_account::<PendingConfigChange>(pending_config_change)
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn set_vault_deposit_limit(
+        &self,
+        test: &mut ProgramTestContext,
+        quote_deposit_limit: u64,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::SetVaultDepositLimit {
+            vault: self.address,
+            authority: self.authority.pubkey(),
+        };
+
+        let ix_data = vaults::instruction::SetVaultDepositLimit {
+            quote_deposit_limit,
+        }
+        .data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn propose_authority(
+        &self,
+        test: &mut ProgramTestContext,
+        pending_authority: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::ProposeAuthority {
+            vault: self.address,
+            authority: self.authority.pubkey(),
+        };
+
+        let ix_data = vaults::instruction::ProposeAuthority { pending_authority }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn accept_authority(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::AcceptAuthority {
+            vault: self.address,
+            pending_authority: user.keypair.pubkey(),
+        };
+
+        let ix_data = vaults::instruction::AcceptAuthority {}.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&user.keypair]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn set_manager(
+        &self,
+        test: &mut ProgramTestContext,
+        manager: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::SetManager {
+            vault: self.address,
+            authority: self.authority.pubkey(),
+        };
+
+        let ix_data = vaults::instruction::SetManager { manager }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn pause(
+        &self,
+        test: &mut ProgramTestContext,
+        withdraw_only: bool,
+    ) -> Result<(), BanksClientError> {
+        self.pause_as(test, &self.authority, withdraw_only).await
+    }
+
+    /// Pauses the vault, signed by the given authority or guardian.
+    #[allow(dead_code)]
+    pub async fn pause_as(
+        &self,
+        test: &mut ProgramTestContext,
+        signer: &Keypair,
+        withdraw_only: bool,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::Pause {
+            vault: self.address,
+            signer: signer.pubkey(),
+        };
+
+        let ix_data = vaults::instruction::Pause { withdraw_only }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn set_guardian(
+        &self,
+        test: &mut ProgramTestContext,
+        guardian: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::SetGuardian {
+            vault: self.address,
+            authority: self.authority.pubkey(),
+        };
+
+        let ix_data = vaults::instruction::SetGuardian { guardian }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn wind_down(&self, test: &mut ProgramTestContext) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::WindDown {
+            vault: self.address,
+            authority: self.authority.pubkey(),
+        };
+
+        let ix_data = vaults::instruction::WindDown {}.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn claim_wind_down(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        lp_token_account: Pubkey,
+        lp_amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::ClaimWindDown {
+            vault: self.address,
+            lp_mint: self.lp_token_mint(&token_mint),
+            lp_token_account,
+            cache_account: test.cache,
+            clearing: test.clearing,
+            cypher_account: self.cypher_account,
+            cypher_sub_account: self.cypher_sub_account,
+            token_mint,
+            // only used by the withdrawals of the claimed SPL Tokens
+            vault_signer: Pubkey::new_unique(),
+            authority: user.keypair.pubkey(),
+            token_program: token::ID,
+            cypher_program: cypher_client::id(),
+        };
+
+        let ix_data = vaults::instruction::ClaimWindDown { lp_amount }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&user.keypair]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn close_vault(
+        &self,
+        test: &mut ProgramTestContext,
+        authority: &Keypair,
+        cypher_sub_account: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::CloseVault {
+            vault: self.address,
+            cache_account: test.cache,
+            cypher_account: self.cypher_account,
+            cypher_sub_account,
+            authority: authority.pubkey(),
+            rent_destination: authority.pubkey(),
+            cypher_program: cypher_client::id(),
+        };
+
+        let ix_data = vaults::instruction::CloseVault {}.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[authority]))
+            .await
+    }
+
+    /// Sweeps the dust of an SPL Token left in the primary sub account.
+    ///
+    /// The Cypher pool accounts are placeholders, so nothing can actually be swept.
+    #[allow(dead_code)]
+    pub async fn sweep_dust(
+        &self,
+        test: &mut ProgramTestContext,
+        token_mint: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let (pool, pool_node, token_vault) = add_pool_accounts(test, &token_mint).await;
+        let destination_token_account = test
+            .create_token_account(&self.authority.pubkey(), &token_mint)
+            .await;
+
+        let accounts = vaults::accounts::SweepDust {
+            vault: self.address,
+            cache_account: test.cache,
+            clearing: test.clearing,
+            cypher_account: self.cypher_account,
+            cypher_sub_account: self.cypher_sub_account,
+            pool,
+            pool_node,
+            token_vault,
+            destination_token_account,
+            token_mint,
+            vault_signer: Pubkey::new_unique(),
+            authority: self.authority.pubkey(),
+            token_program: token::ID,
+            cypher_program: cypher_client::id(),
+        };
+
+        let ix_data = vaults::instruction::SweepDust {}.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    /// Creates a strategy sub account, returning its address.
+    #[allow(dead_code)]
+    pub async fn create_strategy_sub_account(
+        &self,
+        test: &mut ProgramTestContext,
+        sub_account_number: u8,
+        risk_params: RiskParams,
+    ) -> Result<Pubkey, BanksClientError> {
+        let (cypher_sub_account, sub_account_bump) =
+            derive_sub_account_address(&self.cypher_account, sub_account_number);
+
+        let accounts = vaults::accounts::CreateStrategySubAccount {
+            vault: self.address,
+            clearing: test.clearing,
+            cypher_account: self.cypher_account,
+            cypher_sub_account,
+            authority: self.authority.pubkey(),
+            payer: self.authority.pubkey(),
+            system_program: system_program::id(),
+            cypher_program: cypher_client::id(),
+        };
+
+        let ix_data = vaults::instruction::CreateStrategySubAccount {
+            args: CreateStrategySubAccountArgs {
+                sub_account_number,
+                sub_account_bump,
+                sub_account_alias: [0; 32],
+                risk_params,
+            },
+        }
+        .data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await?;
+
+        Ok(cypher_sub_account)
+    }
+
+    /// Cancels all orders of the given Cypher accounts on a market, the market accounts themselves are left out
+    /// as the placeholder markets can not be traded.
+    #[allow(dead_code)]
+    pub async fn cancel_all_orders(
+        &self,
+        test: &mut ProgramTestContext,
+        cypher_account: Pubkey,
+        cypher_sub_account: Pubkey,
+        market_type: MarketType,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::Trade {
+            vault: self.address,
+            cache_account: test.cache,
+            clearing: test.clearing,
+            cypher_account,
+            cypher_sub_account,
+            manager: self.authority.pubkey(),
+            cypher_program: cypher_client::id(),
+        };
+
+        let ix_data = vaults::instruction::CancelAllOrders { market_type }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn transfer_collateral(
+        &self,
+        test: &mut ProgramTestContext,
+        from_sub_account: Pubkey,
+        to_sub_account: Pubkey,
+        pool: Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        self.transfer_collateral_as(
+            test,
+            &self.authority,
+            from_sub_account,
+            to_sub_account,
+            pool,
+            amount,
+        )
+        .await
+    }
+
+    /// Transfers collateral between two sub accounts, signed by the given manager.
+    #[allow(dead_code)]
+    pub async fn transfer_collateral_as(
+        &self,
+        test: &mut ProgramTestContext,
+        manager: &Keypair,
+        from_sub_account: Pubkey,
+        to_sub_account: Pubkey,
+        pool: Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::TransferCollateral {
+            vault: self.address,
+            cache_account: test.cache,
+            clearing: test.clearing,
+            cypher_account: self.cypher_account,
+            from_sub_account,
+            to_sub_account,
+            pool,
+            manager: manager.pubkey(),
+            cypher_program: cypher_client::id(),
+        };
+
+        let ix_data = vaults::instruction::TransferCollateral { amount }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[manager]))
+            .await
+    }
+
+    /// Adds a [`Vault`] in the original layout without any SPL Tokens, along with the LP token Mint it issued.
+    #[allow(dead_code)]
+    pub fn create_v0_vault(
+        test: &mut ProgramTestContext,
+        id: u64,
+        vault_type: VaultType,
+    ) -> VaultCookie {
+        let authority = Keypair::new();
+        test.add_account(&authority.pubkey());
+
+        let (vault, vault_bump) = VaultCookie::derive_vault_address(&authority.pubkey(), id);
+        let (cypher_account, _) = derive_account_address(&vault, 0);
+        let (cypher_sub_account, _) = derive_sub_account_address(&cypher_account, 0);
+
+        let mut data = Vault::discriminator().to_vec();
+        data.extend_from_slice(&[0, vault_bump, 0, 0, vault_type as u8, 0, 0, 0]);
+        data.extend_from_slice(&id.to_le_bytes());
+        data.extend_from_slice(authority.pubkey().as_ref());
+        data.extend_from_slice(&[0; 32]);
+        data.extend_from_slice(&0u32.to_le_bytes());
+        test.add_raw_account(&vault, data, &vaults::id());
+
+        // the original layout issued a single LP token per vault
+        let (lp_mint, _) =
+            Pubkey::find_program_address(&[LP_TOKEN_SEED, vault.as_ref()], &vaults::id());
+        test.add_packable_account(
+            &lp_mint,
+            u32::MAX as u64,
+            &spl_token::state::Mint {
+                mint_authority: COption::Some(vault),
+                is_initialized: true,
+                ..Default::default()
+            },
+            &spl_token::id(),
+        );
+
+        VaultCookie {
+            authority,
+            address: vault,
+            cypher_account,
+            cypher_sub_account,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn migrate_vault(
+        &self,
+        test: &mut ProgramTestContext,
+        id: u64,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::MigrateVault {
+            vault: self.address,
+            authority: self.authority.pubkey(),
+            payer: self.authority.pubkey(),
+            system_program: system_program::id(),
+            rent: rent::Rent::id(),
+        };
+
+        let ix_data = vaults::instruction::MigrateVault {
+            args: MigrateVaultArgs { id },
+        }
+        .data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn create_vault_lp_mint(
+        &self,
+        test: &mut ProgramTestContext,
+        decimals: u8,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::CreateVaultLpMint {
+            vault: self.address,
+            lp_token_mint: self.vault_lp_token_mint(),
+            authority: self.authority.pubkey(),
+            payer: self.authority.pubkey(),
+            system_program: system_program::id(),
+            token_program: token::ID,
+            rent: rent::Rent::id(),
+        };
+
+        let ix_data = vaults::instruction::CreateVaultLpMint { decimals }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    /// Gets the address of the vault-wide LP token Mint.
+    #[allow(dead_code)]
+    pub fn vault_lp_token_mint(&self) -> Pubkey {
+        Pubkey::find_program_address(&[VAULT_LP_TOKEN_SEED, self.address.as_ref()], &vaults::id()).0
+    }
+
+    /// Overwrites the state of the vault with `f` applied, to reach states that need funds in Cypher.
+    #[allow(dead_code)]
+    pub async fn set_vault_state(&self, test: &mut ProgramTestContext, f: impl FnOnce(&mut Vault)) {
+        let mut vault = test.get_zero_copy_account::<Vault>(self.address).await;
+        f(&mut vault);
+        test.add_zero_copy_account(&self.address, vault, &vaults::id());
+    }
+
+    #[allow(dead_code)]
+    pub async fn queue_deposit(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        source_token_account: Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let accounts = vaults::accounts::QueueDeposit {
+            vault: self.address,
+            epoch_ticket: self.epoch_ticket(&user.keypair.pubkey()).0,
+            escrow_token_account: self.epoch_token_escrow(),
+            cache_account: test.cache,
+            cypher_sub_account: self.cypher_sub_account,
+            source_token_account,
+            token_mint,
+            authority: user.keypair.pubkey(),
+            payer: user.keypair.pubkey(),
+            system_program: system_program::id(),
+            token_program: token::ID,
+            rent: rent::Rent::id(),
+        };
+
+        let ix_data = vaults::instruction::QueueDeposit { amount }.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&user.keypair]))
+            .await
+    }
+
+    /// Rolls the current epoch.
+    ///
+    /// The Cypher pool accounts are placeholders, so only rolls that do not move funds in or out of Cypher go through.
+    #[allow(dead_code)]
+    pub async fn roll_epoch(
+        &self,
+        test: &mut ProgramTestContext,
+        token_mint: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let vault = test.get_zero_copy_account::<Vault>(self.address).await;
+        let lp_mint = self.lp_token_mint(&token_mint);
+        let fee_token_account = test
+            .create_token_account(&vault.fee_recipient, &lp_mint)
+            .await;
+        let (pool, pool_node, token_vault) = add_pool_accounts(test, &token_mint).await;
+
+        let accounts = vaults::accounts::RollEpoch {
+            vault: self.address,
+            lp_mint,
+            fee_token_account,
+            epoch_record: self.epoch_record(vault.epoch),
+            escrow_token_account: self.epoch_token_escrow(),
+            escrow_lp_token_account: self.epoch_lp_escrow(),
+            cache_account: test.cache,
+            clearing: test.clearing,
+            cypher_account: self.cypher_account,
+            cypher_sub_account: self.cypher_sub_account,
+            pool,
+            pool_node,
+            token_vault,
+            token_mint,
+            vault_signer: Pubkey::new_unique(),
+            keeper: self.authority.pubkey(),
+            system_program: system_program::id(),
+            token_program: token::ID,
+            cypher_program: cypher_client::id(),
+            rent: rent::Rent::id(),
+        };
+
+        let ix_data = vaults::instruction::RollEpoch {}.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn claim_epoch(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        lp_token_account: Pubkey,
+        destination_token_account: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let owner = user.keypair.pubkey();
+        let epoch_ticket = self.epoch_ticket(&owner).0;
+        let ticket = test.load_anchor_account::<EpochTicket>(epoch_ticket).await;
+        let depositor_position = self.depositor_position(&token_mint, &owner).0;
+
+        let accounts = vaults::accounts::ClaimEpoch {
+            vault: self.address,
+            lp_mint: self.lp_token_mint(&token_mint),
+            epoch_ticket,
+            epoch_record: self.epoch_record(ticket.epoch),
+            escrow_token_account: self.epoch_token_escrow(),
+            escrow_lp_token_account: self.epoch_lp_escrow(),
+            depositor_position,
+            lockup_escrow_token_account: self.lockup_escrow(&depositor_position),
+            lp_token_account,
+            destination_token_account,
+            token_mint,
+            authority: owner,
+            payer: owner,
+            system_program: system_program::id(),
+            token_program: token::ID,
+            rent: rent::Rent::id(),
+        };
+
+        let ix_data = vaults::instruction::ClaimEpoch {}.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&user.keypair]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn release_lockup(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        lp_token_account: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let owner = user.keypair.pubkey();
+        let depositor_position = self.depositor_position(&token_mint, &owner).0;
+
+        let accounts = vaults::accounts::ReleaseLockup {
+            vault: self.address,
+            lp_mint: self.lp_token_mint(&token_mint),
+            lp_token_account,
+            depositor_position,
+            lockup_escrow_token_account: self.lockup_escrow(&depositor_position),
+            token_mint,
+            owner,
+            token_program: token::ID,
+        };
+
+        let ix_data = vaults::instruction::ReleaseLockup {}.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&user.keypair]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn request_withdraw(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+        lp_token_account: Pubkey,
+        lp_amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<(), BanksClientError> {
+        let owner = user.keypair.pubkey();
+        let withdraw_ticket = self.withdraw_ticket(&token_mint, &owner);
+
+        let accounts = vaults::accounts::RequestWithdraw {
+            vault: self.address,
+            lp_mint: self.lp_token_mint(&token_mint),
+            lp_token_account,
+            depositor_position: self.depositor_position(&token_mint, &owner).0,
+            withdraw_ticket,
+            escrow_token_account: self.withdraw_escrow(&withdraw_ticket),
+            token_mint,
+            authority: owner,
+            payer: owner,
+            system_program: system_program::id(),
+            token_program: token::ID,
+            rent: rent::Rent::id(),
+        };
+
+        let ix_data = vaults::instruction::RequestWithdraw {
+            lp_amount,
+            min_tokens_out,
+        }
+        .data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&user.keypair]))
+            .await
+    }
+
+    /// Processes the user's [`vaults::WithdrawTicket`] with the authority as the keeper.
+    ///
+    /// The Cypher pool accounts are placeholders, so only settlements that are rejected before funds move go through.
+    #[allow(dead_code)]
+    pub async fn process_withdraw(
+        &self,
+        test: &mut ProgramTestContext,
+        user: &UserCookie,
+        token_mint: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let owner = user.keypair.pubkey();
+        let lp_mint = self.lp_token_mint(&token_mint);
+        let withdraw_ticket = self.withdraw_ticket(&token_mint, &owner);
+        let fee_token_account = test
+            .create_token_account(&self.authority.pubkey(), &lp_mint)
+            .await;
+        let destination_token_account = test.create_token_account(&owner, &token_mint).await;
+        let (pool, pool_node, token_vault) = add_pool_accounts(test, &token_mint).await;
+
+        let accounts = vaults::accounts::ProcessWithdraw {
+            vault: self.address,
+            lp_mint,
+            fee_token_account,
+            withdraw_ticket,
+            escrow_token_account: self.withdraw_escrow(&withdraw_ticket),
+            depositor_position: self.depositor_position(&token_mint, &owner).0,
+            cache_account: test.cache,
+            clearing: test.clearing,
+            cypher_account: self.cypher_account,
+            cypher_sub_account: self.cypher_sub_account,
+            pool,
+            pool_node,
+            token_vault,
+            destination_token_account,
+            token_mint,
+            vault_signer: Pubkey::new_unique(),
+            owner,
+            keeper: self.authority.pubkey(),
+            token_program: token::ID,
+            cypher_program: cypher_client::id(),
+        };
+
+        let ix_data = vaults::instruction::ProcessWithdraw {}.data();
+
+        test.send_anchor_ix(vaults::id(), &accounts, ix_data, Some(&[&self.authority]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub fn withdraw_ticket(&self, token_mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                WITHDRAW_TICKET_SEED,
+                self.address.as_ref(),
+                token_mint.as_ref(),
+                owner.as_ref(),
+            ],
+            &vaults::id(),
+        )
+        .0
+    }
+
+    #[allow(dead_code)]
+    pub fn withdraw_escrow(&self, withdraw_ticket: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[WITHDRAW_ESCROW_SEED, withdraw_ticket.as_ref()],
+            &vaults::id(),
+        )
+        .0
+    }
+
+    #[allow(dead_code)]
+    pub fn epoch_ticket(&self, owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[EPOCH_TICKET_SEED, self.address.as_ref(), owner.as_ref()],
+            &vaults::id(),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn epoch_record(&self, epoch: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                EPOCH_RECORD_SEED,
+                self.address.as_ref(),
+                epoch.to_le_bytes().as_ref(),
+            ],
+            &vaults::id(),
+        )
+        .0
+    }
+
+    #[allow(dead_code)]
+    pub fn epoch_token_escrow(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[EPOCH_TOKEN_ESCROW_SEED, self.address.as_ref()],
+            &vaults::id(),
+        )
+        .0
+    }
+
+    #[allow(dead_code)]
+    pub fn epoch_lp_escrow(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[EPOCH_LP_ESCROW_SEED, self.address.as_ref()],
+            &vaults::id(),
+        )
+        .0
+    }
+
+    #[allow(dead_code)]
+    pub fn depositor_position(&self, token_mint: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                DEPOSITOR_POSITION_SEED,
+                self.address.as_ref(),
+                token_mint.as_ref(),
+                owner.as_ref(),
+            ],
+            &vaults::id(),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn lockup_escrow(&self, depositor_position: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[LOCKUP_ESCROW_SEED, depositor_position.as_ref()],
+            &vaults::id(),
+        )
+        .0
+    }
+
+    /// Gets the address of the LP token Mint issued for deposits of a given SPL Token Mint.
+    #[allow(dead_code)]
+    pub fn lp_token_mint(&self, token_mint: &Pubkey) -> Pubkey {
+        VaultCookie::derive_lp_mint_address(&self.address, token_mint).0
+    }
+
     fn derive_vault_address(authority: &Pubkey, id: u64) -> (Pubkey, u8) {
         Pubkey::find_program_address(
             &[VAULT_SEED, authority.as_ref(), id.to_le_bytes().as_ref()],
@@ -119,13 +1231,39 @@ impl VaultCookie {
     }
 }
 
-pub async fn init_new_test() -> Result<(ProgramTestContext), BanksClientError> {
+/// Asserts that the transaction failed with the given custom program error.
+#[allow(dead_code)]
+/// Adds placeholder Cypher pool accounts for the SPL Token, returning the pool, its node and the node's token vault.
+async fn add_pool_accounts(
+    test: &mut ProgramTestContext,
+    token_mint: &Pubkey,
+) -> (Pubkey, Pubkey, Pubkey) {
+    let pool = test.add_pool(token_mint);
+    let pool_node = Pubkey::new_unique();
+    test.add_zero_copy_account::<PoolNode>(
+        &pool_node,
+        Box::new(bytemuck::Zeroable::zeroed()),
+        &cypher_client::id(),
+    );
+    let token_vault = test.create_token_account(&pool_node, token_mint).await;
+    (pool, pool_node, token_vault)
+}
+
+pub fn assert_custom_error<T: std::fmt::Debug>(
+    result: Result<T, BanksClientError>,
+    error: impl Into<u32>,
+) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+    );
+}
+
+pub async fn init_new_test() -> Result<ProgramTestContext, BanksClientError> {
     let config = ProgramTestContextConfig {
         mint_decimals: vec![],
     };
     let test = ProgramTestContext::start_new(&config).await;
 
-    let cache_account = Keypair::new();
-
-    Ok((test))
+    Ok(test)
 }
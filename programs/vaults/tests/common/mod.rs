@@ -1,5 +1,6 @@
 pub mod context;
 pub mod cookies;
+pub mod state;
 
 pub use context::*;
 pub use cookies::*;
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::Pubkey;
+use vaults::{DepositorPosition, OpenDepositsArgs, RiskParams, TokenInfo, Vault};
+
+/// A [`TokenInfo`] for a new token mint, with the given LP token supply.
+#[allow(dead_code)]
+pub fn new_token_info(token_supply: u64) -> TokenInfo {
+    let mut token_info = TokenInfo::new(
+        &OpenDepositsArgs {
+            token_mint: Pubkey::new_unique(),
+            deposit_limit: u64::MAX,
+            ..Default::default()
+        },
+        Pubkey::new_unique(),
+    );
+    token_info.token_supply = token_supply;
+    token_info
+}
+
+/// A zeroed [`Vault`] with deposits open for a single new token mint, returned alongside it.
+#[allow(dead_code)]
+pub fn new_vault() -> (Box<Vault>, Pubkey) {
+    let mut vault: Box<Vault> = Box::new(bytemuck::Zeroable::zeroed());
+    let token_info = new_token_info(0);
+    let token_mint = token_info.token_mint;
+    vault.add_token_info(token_info).unwrap();
+    (vault, token_mint)
+}
+
+/// A new [`DepositorPosition`] of some vault, token mint and owner.
+#[allow(dead_code)]
+pub fn new_position() -> DepositorPosition {
+    let mut position = DepositorPosition::default();
+    position.init(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        255,
+    );
+    position
+}
+
+/// [`RiskParams`] that only limit the margin ratio of a strategy sub account.
+#[allow(dead_code)]
+pub fn new_risk_params(min_margin_ratio: u16) -> RiskParams {
+    let mut risk_params = RiskParams::default();
+    risk_params.min_margin_ratio = min_margin_ratio;
+    risk_params
+}
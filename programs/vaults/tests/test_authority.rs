@@ -0,0 +1,45 @@
+mod common;
+
+use crate::common::cookies::{assert_custom_error, init_new_test, UserCookie, VaultCookie};
+use anchor_lang::{error::ErrorCode as AnchorErrorCode, prelude::Pubkey};
+use solana_sdk::signer::Signer;
+use vaults::{error::ErrorCode, Vault};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn authority_is_only_transferred_once_accepted() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    let new_authority = UserCookie::new(&mut test).unwrap();
+    let stranger = UserCookie::new(&mut test).unwrap();
+
+    // nothing can be accepted before it is proposed
+    let res = vault.accept_authority(&mut test, &new_authority).await;
+    assert_custom_error(res, ErrorCode::InvalidPendingAuthority);
+
+    vault
+        .propose_authority(&mut test, new_authority.keypair.pubkey())
+        .await
+        .unwrap();
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.authority, vault.authority.pubkey());
+    assert_eq!(
+        vault_state.pending_authority,
+        new_authority.keypair.pubkey()
+    );
+
+    let res = vault.accept_authority(&mut test, &stranger).await;
+    assert_custom_error(res, ErrorCode::InvalidPendingAuthority);
+
+    test.advance_clock_by_slots(1).await;
+    vault
+        .accept_authority(&mut test, &new_authority)
+        .await
+        .unwrap();
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.authority, new_authority.keypair.pubkey());
+    assert_eq!(vault_state.pending_authority, Pubkey::default());
+
+    // the previous authority has handed over every authority instruction
+    let res = vault.set_manager(&mut test, Pubkey::new_unique()).await;
+    assert_custom_error(res, AnchorErrorCode::ConstraintHasOne);
+}
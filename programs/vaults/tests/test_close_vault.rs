@@ -1,8 +1,95 @@
 mod common;
 
-use crate::common::cookies::init_new_test;
+use crate::common::cookies::{assert_custom_error, init_new_test, VaultCookie};
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use cypher_client::{quote_mint, CypherSubAccount};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use vaults::error::ErrorCode;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn close_vault() {
-    let mut test = init_new_test().await.ok().unwrap();
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+
+    vault
+        .close_vault(&mut test, &vault.authority, vault.cypher_sub_account)
+        .await
+        .unwrap();
+
+    for address in [
+        vault.address,
+        vault.cypher_account,
+        vault.cypher_sub_account,
+    ] {
+        assert!(test.load_account_result(address).await.unwrap().is_none());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn close_vault_requires_authority() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+
+    let res = vault
+        .close_vault(&mut test, &Keypair::new(), vault.cypher_sub_account)
+        .await;
+    assert_custom_error(res, AnchorErrorCode::ConstraintHasOne);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn close_vault_requires_vault_sub_account() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+
+    let cypher_sub_account = Pubkey::new_unique();
+    test.add_zero_copy_account::<CypherSubAccount>(
+        &cypher_sub_account,
+        Box::new(bytemuck::Zeroable::zeroed()),
+        &cypher_client::id(),
+    );
+
+    let res = vault
+        .close_vault(&mut test, &vault.authority, cypher_sub_account)
+        .await;
+    assert_custom_error(res, ErrorCode::InvalidCypherAccount);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn close_vault_requires_settled_flows() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+
+    // a withdrawal is still waiting to be processed
+    vault
+        .set_vault_state(&mut test, |v| v.withdraw_ticket_count = 1)
+        .await;
+
+    let res = vault
+        .close_vault(&mut test, &vault.authority, vault.cypher_sub_account)
+        .await;
+    assert_custom_error(res, ErrorCode::UnsettledFlows);
+
+    let res = vault.sweep_dust(&mut test, quote_mint::id()).await;
+    assert_custom_error(res, ErrorCode::UnsettledFlows);
+
+    // a deposit is still queued in the current epoch
+    vault
+        .set_vault_state(&mut test, |v| {
+            v.withdraw_ticket_count = 0;
+            v.pending_deposits = 1;
+        })
+        .await;
+
+    let res = vault
+        .close_vault(&mut test, &vault.authority, vault.cypher_sub_account)
+        .await;
+    assert_custom_error(res, ErrorCode::UnsettledFlows);
 }
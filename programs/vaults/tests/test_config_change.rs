@@ -0,0 +1,71 @@
+mod common;
+
+use crate::common::cookies::{assert_custom_error, init_new_test, VaultCookie};
+use vaults::{error::ErrorCode, ConfigChange, Vault};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn timelocked_vault_rejects_direct_changes() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault_with_timelock(&mut test, 0, 60)
+        .await
+        .unwrap();
+
+    let res = vault.set_vault_deposit_limit(&mut test, 1_000_000).await;
+    assert_custom_error(res, ErrorCode::ConfigChangeTimelocked);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn config_change_executes_after_timelock() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault_with_timelock(&mut test, 0, 60)
+        .await
+        .unwrap();
+
+    let pending_config_change = vault
+        .queue_config_change(
+            &mut test,
+            ConfigChange::VaultDepositLimit {
+                quote_deposit_limit: 1_000_000,
+            },
+        )
+        .await
+        .unwrap();
+    let queued_change = vault
+        .get_pending_config_change(&mut test, pending_config_change)
+        .await;
+    assert_eq!(queued_change.executable_at, queued_change.queued_at + 60);
+
+    let res = vault
+        .execute_config_change(&mut test, pending_config_change)
+        .await;
+    assert_custom_error(res, ErrorCode::ConfigChangeNotExecutable);
+
+    test.advance_clock_past_timestamp(queued_change.executable_at)
+        .await;
+    vault
+        .execute_config_change(&mut test, pending_config_change)
+        .await
+        .unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.quote_deposit_limit, 1_000_000);
+    assert!(test
+        .load_account_result(pending_config_change)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vault_without_timelock_allows_direct_changes() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+
+    vault
+        .set_vault_deposit_limit(&mut test, 1_000_000)
+        .await
+        .unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.quote_deposit_limit, 1_000_000);
+}
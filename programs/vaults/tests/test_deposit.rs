@@ -0,0 +1,95 @@
+mod common;
+
+use crate::common::cookies::{assert_custom_error, init_new_test, UserCookie, VaultCookie};
+use cypher_client::quote_mint;
+use solana_sdk::signer::Signer;
+use vaults::{error::ErrorCode, DepositorPosition};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deposit_requires_enabled_token_within_its_limit() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+    let user = UserCookie::new(&mut test).unwrap();
+
+    vault
+        .set_vault_state(&mut test, |vault| {
+            vault.token_infos_mut()[0].deposit_limit = 1
+        })
+        .await;
+    let res = vault
+        .deposit(&mut test, &user, quote_mint::id(), 2, 0)
+        .await;
+    assert_custom_error(res, ErrorCode::DepositLimitExceeded);
+
+    vault
+        .disable_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+    let res = vault
+        .deposit(&mut test, &user, quote_mint::id(), 1, 0)
+        .await;
+    assert_custom_error(res, ErrorCode::DepositsDisabled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deposit_and_withdraw_respect_their_slippage_bounds() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+    let user = UserCookie::new(&mut test).unwrap();
+
+    let res = vault
+        .deposit(&mut test, &user, quote_mint::id(), 1_000, u64::MAX)
+        .await;
+    assert_custom_error(res, ErrorCode::SlippageExceeded);
+
+    // withdrawing from a vault without assets burns more LP tokens than the user accepts
+    let res = vault
+        .withdraw(&mut test, &user, quote_mint::id(), 1, 1)
+        .await;
+    assert_custom_error(res, ErrorCode::SlippageExceeded);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deposit_for_requires_approval_while_locked_up() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits_with_lockup(&mut test, quote_mint::id(), 60)
+        .await
+        .unwrap();
+    let funder = UserCookie::new(&mut test).unwrap();
+    let beneficiary = UserCookie::new(&mut test).unwrap();
+
+    let res = vault
+        .deposit_for(
+            &mut test,
+            &funder,
+            beneficiary.keypair.pubkey(),
+            quote_mint::id(),
+            1_000,
+        )
+        .await;
+    assert_custom_error(res, ErrorCode::DepositForNotApproved);
+
+    vault
+        .approve_deposits_for(&mut test, &beneficiary, quote_mint::id(), true)
+        .await
+        .unwrap();
+    let position = test
+        .load_anchor_account::<DepositorPosition>(
+            vault
+                .depositor_position(&quote_mint::id(), &beneficiary.keypair.pubkey())
+                .0,
+        )
+        .await;
+    assert_eq!(position.owner, beneficiary.keypair.pubkey());
+    assert!(position.approves_deposits_for);
+}
@@ -0,0 +1,214 @@
+mod common;
+
+use crate::common::cookies::{assert_custom_error, init_new_test, UserCookie, VaultCookie};
+use anchor_lang::prelude::Pubkey;
+use cypher_client::quote_mint;
+use solana_sdk::signer::Signer;
+use vaults::{error::ErrorCode, CreateVaultArgs, EpochRecord, EpochTicket, Vault};
+
+#[test]
+fn epoch_rolls_after_its_duration() {
+    let mut vault: Box<Vault> = Box::new(bytemuck::Zeroable::zeroed());
+    vault.init(
+        Pubkey::new_unique(),
+        255,
+        &CreateVaultArgs {
+            epoch_duration: 3_600,
+            ..Default::default()
+        },
+        100,
+    );
+    vault.pending_deposits = 1_000;
+    vault.pending_withdrawals = 2_000;
+
+    assert!(vault.is_epoch_mode());
+    assert!(!vault.is_epoch_over(3_699));
+    assert!(vault.is_epoch_over(3_700));
+
+    vault.start_next_epoch(3_750).unwrap();
+    assert_eq!(vault.epoch, 1);
+    assert_eq!(vault.epoch_start, 3_750);
+    assert_eq!(vault.pending_deposits, 0);
+    assert_eq!(vault.pending_withdrawals, 0);
+    assert!(!vault.is_epoch_over(3_750));
+}
+
+#[test]
+fn ticket_only_queues_in_a_single_epoch() {
+    let mut ticket = EpochTicket::default();
+    ticket.init(Pubkey::new_unique(), Pubkey::new_unique(), 255);
+    assert!(ticket.can_queue(3));
+
+    ticket.queue_deposit(3, 1_000).unwrap();
+    ticket.queue_withdraw(3, 500).unwrap();
+    ticket.queue_deposit(3, 1_000).unwrap();
+    assert_eq!(ticket.deposit_amount, 2_000);
+    assert_eq!(ticket.lp_amount, 500);
+    assert!(ticket.can_queue(3));
+
+    // what was queued in an earlier epoch has to be claimed first
+    assert!(!ticket.can_queue(4));
+    ticket.deposit_amount = 0;
+    ticket.lp_amount = 0;
+    assert!(ticket.can_queue(4));
+}
+
+#[test]
+fn claims_are_pro_rata_to_the_epoch() {
+    let record = EpochRecord {
+        deposits: 3_000,
+        lp_minted: 3_000_000,
+        lp_burned: 900_000,
+        withdrawn: 1_000,
+        ..Default::default()
+    };
+
+    assert_eq!(record.calculate_claim_lp_amount(1_000).unwrap(), 1_000_000);
+    assert_eq!(record.calculate_claim_token_amount(300_000).unwrap(), 333);
+    assert_eq!(record.calculate_claim_token_amount(900_000).unwrap(), 1_000);
+
+    // nothing was processed in an empty epoch
+    let record = EpochRecord::default();
+    assert_eq!(record.calculate_claim_lp_amount(1_000).unwrap(), 0);
+    assert_eq!(record.calculate_claim_token_amount(1_000).unwrap(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn claimed_lp_is_held_in_the_lockup_escrow() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault_with_args(&mut test, 0, |args| {
+        args.epoch_duration = 3_600;
+    })
+    .await
+    .unwrap();
+    vault
+        .open_deposits_with_lockup(&mut test, quote_mint::id(), 60)
+        .await
+        .unwrap();
+
+    let user = UserCookie::new(&mut test).unwrap();
+    let owner = user.keypair.pubkey();
+    let lp_mint = vault.lp_token_mint(&quote_mint::id());
+    let lp_token_account = test.create_token_account(&owner, &lp_mint).await;
+    let destination_token_account = test.create_token_account(&owner, &quote_mint::id()).await;
+
+    // the epoch the deposit was queued in has been rolled, its LP tokens wait in the epoch escrow
+    vault
+        .set_vault_state(&mut test, |v| {
+            v.epoch = 1;
+            v.epoch_ticket_count = 1;
+        })
+        .await;
+    let (epoch_ticket, ticket_bump) = vault.epoch_ticket(&owner);
+    test.add_anchor_account(
+        &epoch_ticket,
+        &EpochTicket {
+            bump: ticket_bump,
+            vault: vault.address,
+            owner,
+            epoch: 0,
+            deposit_amount: 1_000,
+            lp_amount: 0,
+        },
+        EpochTicket::LEN,
+    );
+    test.add_anchor_account(
+        &vault.epoch_record(0),
+        &EpochRecord {
+            vault: vault.address,
+            epoch: 0,
+            deposits: 1_000,
+            lp_minted: 1_000_000,
+            ..Default::default()
+        },
+        EpochRecord::LEN,
+    );
+    test.add_token_account(
+        &vault.epoch_token_escrow(),
+        &quote_mint::id(),
+        &vault.address,
+        0,
+    );
+    test.add_token_account(
+        &vault.epoch_lp_escrow(),
+        &lp_mint,
+        &vault.address,
+        1_000_000,
+    );
+
+    vault
+        .claim_epoch(
+            &mut test,
+            &user,
+            quote_mint::id(),
+            lp_token_account,
+            destination_token_account,
+        )
+        .await
+        .unwrap();
+
+    let depositor_position = vault.depositor_position(&quote_mint::id(), &owner).0;
+    let lockup_escrow = vault.lockup_escrow(&depositor_position);
+    assert_eq!(test.get_token_balance(lockup_escrow).await, 1_000_000);
+    assert_eq!(test.get_token_balance(lp_token_account).await, 0);
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.epoch_ticket_count, 0);
+
+    let res = vault
+        .release_lockup(&mut test, &user, quote_mint::id(), lp_token_account)
+        .await;
+    assert_custom_error(res, ErrorCode::DepositLocked);
+
+    let now = test.get_clock().await.unix_timestamp;
+    test.advance_clock_past_timestamp(now + 60).await;
+
+    vault
+        .release_lockup(&mut test, &user, quote_mint::id(), lp_token_account)
+        .await
+        .unwrap();
+    assert_eq!(test.get_token_balance(lockup_escrow).await, 0);
+    assert_eq!(test.get_token_balance(lp_token_account).await, 1_000_000);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn empty_deposit_can_not_be_queued() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault_with_args(&mut test, 0, |args| {
+        args.epoch_duration = 3_600;
+    })
+    .await
+    .unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+
+    let user = UserCookie::new(&mut test).unwrap();
+    let source_token_account = test
+        .create_token_account(&user.keypair.pubkey(), &quote_mint::id())
+        .await;
+
+    let res = vault
+        .queue_deposit(&mut test, &user, quote_mint::id(), source_token_account, 0)
+        .await;
+    assert_custom_error(res, ErrorCode::ZeroAmount);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn epoch_can_not_be_rolled_after_wind_down() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault_with_args(&mut test, 0, |args| {
+        args.epoch_duration = 3_600;
+    })
+    .await
+    .unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+
+    vault.wind_down(&mut test).await.unwrap();
+
+    let res = vault.roll_epoch(&mut test, quote_mint::id()).await;
+    assert_custom_error(res, ErrorCode::VaultWindingDown);
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use crate::common::cookies::{init_new_test, VaultCookie};
+use anchor_lang::prelude::Pubkey;
+use cypher_client::quote_mint;
+use vaults::{DepositsOpened, ManagerSet};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn state_changes_are_emitted() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+    let events = test.get_events::<DepositsOpened>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].vault, vault.address);
+    assert_eq!(events[0].token_mint, quote_mint::id());
+    assert_eq!(events[0].lp_mint, vault.lp_token_mint(&quote_mint::id()));
+    assert_eq!(events[0].deposit_limit, u64::MAX);
+
+    let manager = Pubkey::new_unique();
+    vault.set_manager(&mut test, manager).await.unwrap();
+    let events = test.get_events::<ManagerSet>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].vault, vault.address);
+    assert_eq!(events[0].manager, manager);
+    assert!(test.get_events::<DepositsOpened>().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejected_changes_are_not_emitted() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+
+    // a single token vault accepts no other token
+    assert!(vault
+        .open_deposits(&mut test, Pubkey::new_unique())
+        .await
+        .is_err());
+    assert!(test.get_events::<DepositsOpened>().is_empty());
+}
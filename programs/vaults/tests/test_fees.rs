@@ -0,0 +1,70 @@
+mod common;
+
+use crate::common::state::new_token_info;
+use anchor_lang::{error::Error, prelude::Pubkey};
+use vaults::{error::ErrorCode, SetFeesArgs, Vault, SECONDS_PER_YEAR, SHARE_PRICE_SCALE};
+
+#[test]
+fn first_accrual_sets_high_water_mark() {
+    let mut token_info = new_token_info(1_000_000_000);
+
+    let fee_amount = token_info.accrue_fees(1_000_000, 200, 2_000, 1).unwrap();
+    assert_eq!(fee_amount, 0);
+    assert_eq!(token_info.high_water_mark, 1_000_000);
+    assert_eq!(token_info.last_fee_accrual, 1);
+    assert_eq!(token_info.token_supply, 1_000_000_000);
+}
+
+#[test]
+fn management_fee_accrues_over_time() {
+    let mut token_info = new_token_info(1_000_000_000);
+    token_info.accrue_fees(1_000_000, 200, 0, 1).unwrap();
+
+    let now = 1 + SECONDS_PER_YEAR as i64;
+    let fee_amount = token_info.accrue_fees(1_000_000, 200, 0, now).unwrap();
+    assert_eq!(fee_amount, 20_408_163);
+    assert_eq!(token_info.token_supply, 1_000_000_000 + fee_amount);
+
+    // accruing again at the same timestamp charges nothing
+    let fee_amount = token_info.accrue_fees(1_000_000, 200, 0, now).unwrap();
+    assert_eq!(fee_amount, 0);
+}
+
+#[test]
+fn performance_fee_is_charged_above_high_water_mark() {
+    let mut token_info = new_token_info(1_000_000_000);
+    token_info.accrue_fees(1_000_000, 0, 2_000, 1).unwrap();
+
+    let fee_amount = token_info.accrue_fees(1_200_000, 0, 2_000, 2).unwrap();
+    assert_eq!(fee_amount, 34_481_866);
+    assert_eq!(token_info.high_water_mark, 1_199_999);
+
+    // a drawdown and recovery back to the high-water mark charges nothing
+    let token_supply = token_info.token_supply;
+    assert_eq!(token_info.accrue_fees(1_000_000, 0, 2_000, 3).unwrap(), 0);
+    let net_assets = token_info.high_water_mark as u128 * token_supply as u128 / SHARE_PRICE_SCALE;
+    assert_eq!(token_info.accrue_fees(net_assets, 0, 2_000, 4).unwrap(), 0);
+}
+
+#[test]
+fn set_fees_requires_accrued_fees() {
+    let mut vault: Box<Vault> = Box::new(bytemuck::Zeroable::zeroed());
+    let token_info = new_token_info(1_000_000_000);
+    let token_mint = token_info.token_mint;
+    vault.add_token_info(token_info).unwrap();
+
+    let args = SetFeesArgs {
+        management_fee_bps: 200,
+        performance_fee_bps: 2_000,
+        fee_recipient: Pubkey::new_unique(),
+    };
+    assert_eq!(
+        vault.set_fees(&args, 10).unwrap_err(),
+        Error::from(ErrorCode::FeesNotAccrued)
+    );
+
+    vault.accrue_fees(token_mint, 1_000_000, 10).unwrap();
+    vault.set_fees(&args, 10).unwrap();
+    assert_eq!(vault.management_fee_bps, 200);
+    assert_eq!(vault.performance_fee_bps, 2_000);
+}
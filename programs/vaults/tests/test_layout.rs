@@ -0,0 +1,37 @@
+mod common;
+
+use crate::common::state::new_token_info;
+use anchor_lang::error::Error;
+use vaults::{error::ErrorCode, Vault, MAX_TOKEN_INFOS};
+
+/// The offset of a field of the [`Vault`], in bytes from the start of its data after the discriminator.
+fn offset_of<T>(vault: &Vault, field: &T) -> usize {
+    field as *const T as usize - vault as *const Vault as usize
+}
+
+#[test]
+fn vault_layout_is_fixed() {
+    let vault: Box<Vault> = Box::new(bytemuck::Zeroable::zeroed());
+
+    assert_eq!(std::mem::size_of::<Vault>(), 5792);
+    assert_eq!(Vault::LEN, 8 + 5792);
+    assert_eq!(offset_of(&vault, &vault.guardian), 592);
+    assert_eq!(offset_of(&vault, &vault.token_info_count), 624);
+    assert_eq!(offset_of(&vault, &vault.withdraw_ticket_count), 628);
+    assert_eq!(offset_of(&vault, &vault.wind_down_at), 688);
+    assert_eq!(offset_of(&vault, &vault.epoch_ticket_count), 700);
+}
+
+#[test]
+fn token_list_is_bounded() {
+    let mut vault: Box<Vault> = Box::new(bytemuck::Zeroable::zeroed());
+    for _ in 0..MAX_TOKEN_INFOS {
+        vault.add_token_info(new_token_info(0)).unwrap();
+    }
+    assert_eq!(vault.token_infos().len(), MAX_TOKEN_INFOS);
+
+    assert_eq!(
+        vault.add_token_info(new_token_info(0)).unwrap_err(),
+        Error::from(ErrorCode::TooManyTokens)
+    );
+}
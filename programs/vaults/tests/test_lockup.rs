@@ -0,0 +1,45 @@
+mod common;
+
+use crate::common::state::new_position;
+
+#[test]
+fn deposit_is_locked_for_lockup_period() {
+    let mut position = new_position();
+    position.record_deposit(1_000_000, 1_000_000, 1_000_000_000);
+    position.restart_lockup(100);
+
+    assert!(position.is_locked(60, 100));
+    assert!(position.is_locked(60, 159));
+    assert!(!position.is_locked(60, 160));
+    assert!(!position.is_locked(0, 100));
+}
+
+#[test]
+fn recording_a_deposit_does_not_restart_lockup() {
+    let mut position = new_position();
+    position.restart_lockup(100);
+
+    // deposits only restart the lockup period when the owner made or approved them
+    position.record_deposit(1_000_000, 1_000_000, 1_000_000_000);
+    assert_eq!(position.last_deposit_at, 100);
+    assert!(!position.is_locked(60, 160));
+
+    position.restart_lockup(200);
+    assert!(position.is_locked(60, 200));
+}
+
+#[test]
+fn deposits_update_average_entry_price() {
+    let mut position = new_position();
+    position.record_deposit(1_000_000, 1_000_000, 1_000_000_000);
+    assert_eq!(position.average_entry_price, 1_000_000);
+
+    position.record_deposit(1_000_000, 1_000_000, 500_000_000);
+    assert_eq!(position.average_entry_price, 1_333_333);
+    assert_eq!(position.deposited_amount, 2_000_000);
+    assert_eq!(position.lp_outstanding(), 1_500_000_000);
+
+    position.record_withdraw(1_500_000, 500_000_000);
+    assert_eq!(position.lp_outstanding(), 1_000_000_000);
+    assert_eq!(position.average_entry_price, 1_333_333);
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use crate::common::{
+    cookies::{assert_custom_error, init_new_test, UserCookie, VaultCookie},
+    state::new_risk_params,
+};
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use cypher_client::quote_mint;
+use solana_sdk::signer::Signer;
+use vaults::Vault;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_manager_moves_collateral() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    let sub_account = vault
+        .create_strategy_sub_account(&mut test, 1, new_risk_params(120))
+        .await
+        .unwrap();
+    let pool = test.add_pool(&quote_mint::id());
+
+    let manager = UserCookie::new(&mut test).unwrap();
+    vault
+        .set_manager(&mut test, manager.keypair.pubkey())
+        .await
+        .unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.manager, manager.keypair.pubkey());
+    assert_eq!(vault_state.authority, vault.authority.pubkey());
+
+    // the authority no longer trades once it handed the role to a manager
+    let res = vault
+        .transfer_collateral(&mut test, vault.cypher_sub_account, sub_account, pool, 1)
+        .await;
+    assert_custom_error(res, AnchorErrorCode::ConstraintHasOne);
+}
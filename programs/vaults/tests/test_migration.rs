@@ -0,0 +1,31 @@
+mod common;
+
+use crate::common::cookies::{assert_custom_error, init_new_test, VaultCookie};
+use vaults::{error::ErrorCode, Vault, VaultType, VAULT_VERSION};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unmigrated_vault_is_rejected() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_v0_vault(&mut test, 0, VaultType::MultiToken);
+
+    let res = vault.create_vault_lp_mint(&mut test, 6).await;
+    assert_custom_error(res, ErrorCode::VaultNotMigrated);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vault_lp_mint_can_be_created_after_migration() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_v0_vault(&mut test, 0, VaultType::MultiToken);
+
+    vault.migrate_vault(&mut test, 0).await.unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.version, VAULT_VERSION);
+    assert!(!vault_state.has_vault_lp());
+
+    // the LP token Mint of the original layout is left as it is
+    vault.create_vault_lp_mint(&mut test, 6).await.unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.lp_info.lp_mint, vault.vault_lp_token_mint());
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use crate::common::cookies::{assert_custom_error, init_new_test, VaultCookie};
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::Mint;
+use cypher_client::quote_mint;
+use solana_program::program_option::COption;
+use vaults::{error::ErrorCode, Vault, VaultType};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn open_deposits_registers_the_token() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.token_info_count, 1);
+    let token_info = vault_state.get_token_info(quote_mint::id()).unwrap();
+    assert_eq!(token_info.lp_mint, vault.lp_token_mint(&quote_mint::id()));
+    assert_eq!(token_info.deposit_limit, u64::MAX);
+    assert!(token_info.is_enabled());
+
+    // a single token vault accepts no other token
+    let res = vault.open_deposits(&mut test, Pubkey::new_unique()).await;
+    assert_custom_error(res, ErrorCode::InvalidVaultType);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn multi_token_vault_registers_every_token() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault_with_args(&mut test, 0, |args| {
+        args.vault_type = VaultType::MultiToken;
+    })
+    .await
+    .unwrap();
+    let token_mint = Pubkey::new_unique();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+    vault.open_deposits(&mut test, token_mint).await.unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.token_info_count, 2);
+    assert!(vault_state.get_token_info(quote_mint::id()).is_some());
+    assert!(vault_state.get_token_info(token_mint).is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn every_token_has_its_own_lp_mint() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault_with_args(&mut test, 0, |args| {
+        args.vault_type = VaultType::MultiToken;
+    })
+    .await
+    .unwrap();
+    let token_mint = Pubkey::new_unique();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+    vault.open_deposits(&mut test, token_mint).await.unwrap();
+
+    let quote_lp_mint = vault.lp_token_mint(&quote_mint::id());
+    let token_lp_mint = vault.lp_token_mint(&token_mint);
+    assert_ne!(quote_lp_mint, token_lp_mint);
+
+    for lp_mint in [quote_lp_mint, token_lp_mint] {
+        let mint = test.load_anchor_account::<Mint>(lp_mint).await;
+        assert_eq!(mint.mint_authority, COption::Some(vault.address));
+        assert_eq!(mint.supply, 0);
+    }
+}
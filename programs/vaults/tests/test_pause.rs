@@ -0,0 +1,44 @@
+mod common;
+
+use crate::common::cookies::{assert_custom_error, init_new_test, UserCookie, VaultCookie};
+use solana_sdk::signer::Signer;
+use vaults::{error::ErrorCode, PauseState, Vault};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn guardian_can_only_make_the_pause_stricter() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    let guardian = UserCookie::new(&mut test).unwrap();
+
+    // nobody but the authority can pause a vault without a guardian
+    let res = vault.pause_as(&mut test, &guardian.keypair, true).await;
+    assert_custom_error(res, ErrorCode::InvalidGuardian);
+
+    vault
+        .set_guardian(&mut test, guardian.keypair.pubkey())
+        .await
+        .unwrap();
+    test.advance_clock_by_slots(1).await;
+    vault
+        .pause_as(&mut test, &guardian.keypair, true)
+        .await
+        .unwrap();
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.pause_state(), PauseState::WithdrawOnly);
+
+    vault
+        .pause_as(&mut test, &guardian.keypair, false)
+        .await
+        .unwrap();
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.pause_state(), PauseState::Paused);
+
+    test.advance_clock_by_slots(1).await;
+    let res = vault.pause_as(&mut test, &guardian.keypair, true).await;
+    assert_custom_error(res, ErrorCode::InvalidPauseState);
+
+    // the authority can relax the pause
+    vault.pause(&mut test, true).await.unwrap();
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.pause_state(), PauseState::WithdrawOnly);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use crate::common::state::new_token_info;
+use vaults::{SHARE_PRICE_SCALE, VIRTUAL_SHARES};
+
+#[test]
+fn first_deposit_mints_virtual_shares_per_unit() {
+    let token_info = new_token_info(0);
+
+    let mint_amount = token_info.calculate_mint_amount(1_000_000, 0).unwrap();
+    assert_eq!(mint_amount, 1_000_000 * VIRTUAL_SHARES as u64);
+    assert_eq!(
+        token_info.calculate_share_price(0).unwrap(),
+        SHARE_PRICE_SCALE / VIRTUAL_SHARES
+    );
+}
+
+#[test]
+fn deposit_is_priced_at_marked_to_market_net_assets() {
+    // the vault doubled in value since the first deposit
+    let token_info = new_token_info(1_000_000_000);
+
+    let mint_amount = token_info
+        .calculate_mint_amount(1_000_000, 2_000_000)
+        .unwrap();
+    assert_eq!(mint_amount, 500_000_249);
+
+    let burn_amount = token_info
+        .calculate_burn_amount(1_000_000, 2_000_000)
+        .unwrap();
+    assert_eq!(burn_amount, 500_000_250);
+}
+
+#[test]
+fn share_price_is_the_redeem_price() {
+    let token_info = new_token_info(1_000_000_000);
+
+    let share_price = token_info.calculate_share_price(2_000_000).unwrap();
+    assert_eq!(share_price, 1_999_999);
+    assert_eq!(
+        token_info
+            .calculate_redeem_amount(SHARE_PRICE_SCALE as u64, 2_000_000)
+            .unwrap() as u128,
+        share_price
+    );
+}
+
+#[test]
+fn rounding_favours_the_vault() {
+    let token_info = new_token_info(1_000_000_000);
+    let net_assets = 1_234_567;
+
+    for amount in [1, 7, 999, 123_456] {
+        let mint_amount = token_info
+            .calculate_mint_amount(amount, net_assets)
+            .unwrap();
+        let burn_amount = token_info
+            .calculate_burn_amount(amount, net_assets)
+            .unwrap();
+        assert!(mint_amount <= burn_amount);
+        assert!(
+            token_info
+                .calculate_redeem_amount(mint_amount, net_assets)
+                .unwrap()
+                <= amount
+        );
+        assert!(
+            token_info
+                .calculate_redeem_amount(burn_amount, net_assets)
+                .unwrap()
+                >= amount
+        );
+    }
+}
+
+#[test]
+fn donation_to_the_vault_is_unprofitable() {
+    let mut token_info = new_token_info(0);
+
+    // the attacker deposits a single native unit and donates a large amount to the vault
+    let attacker_lp = token_info.calculate_mint_amount(1, 0).unwrap();
+    token_info.token_supply += attacker_lp;
+    let net_assets = 1 + 1_000_000;
+
+    let victim_lp = token_info
+        .calculate_mint_amount(1_000_000, net_assets)
+        .unwrap();
+    assert_ne!(victim_lp, 0);
+    token_info.token_supply += victim_lp;
+    let net_assets = net_assets + 1_000_000;
+
+    let victim_redeem = token_info
+        .calculate_redeem_amount(victim_lp, net_assets)
+        .unwrap();
+    let attacker_redeem = token_info
+        .calculate_redeem_amount(attacker_lp, net_assets)
+        .unwrap();
+    assert_eq!(victim_redeem, 999_750);
+    assert!(attacker_redeem < 1 + 1_000_000);
+}
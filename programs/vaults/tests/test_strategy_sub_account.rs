@@ -0,0 +1,155 @@
+mod common;
+
+use crate::common::{
+    cookies::{assert_custom_error, init_new_test, VaultCookie},
+    state::new_risk_params,
+};
+use anchor_lang::{error::Error, prelude::Pubkey};
+use cypher_client::{quote_mint, CacheAccount, Clearing, ClearingConfig, CypherSubAccount};
+use vaults::{check_sub_account_risk_limits, error::ErrorCode, Vault};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn create_strategy_sub_account() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+
+    let sub_account = vault
+        .create_strategy_sub_account(&mut test, 1, new_risk_params(120))
+        .await
+        .unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.strategy_sub_account_count, 1);
+    assert_eq!(
+        vault_state.get_risk_params(&vault.address, &sub_account),
+        Some(&new_risk_params(120))
+    );
+    assert!(test
+        .load_account_result(sub_account)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn create_strategy_sub_account_validates_risk_params() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+
+    // the clearing's maintenance margin is 110
+    let res = vault
+        .create_strategy_sub_account(&mut test, 1, new_risk_params(100))
+        .await;
+    assert_custom_error(res, ErrorCode::InvalidMarginRatio);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn create_strategy_sub_account_respects_timelock() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault_with_timelock(&mut test, 0, 60)
+        .await
+        .unwrap();
+
+    let res = vault
+        .create_strategy_sub_account(&mut test, 1, new_risk_params(120))
+        .await;
+    assert_custom_error(res, ErrorCode::ConfigChangeTimelocked);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transfer_collateral_requires_vault_sub_accounts() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    let sub_account = vault
+        .create_strategy_sub_account(&mut test, 1, new_risk_params(120))
+        .await
+        .unwrap();
+    let pool = test.add_pool(&quote_mint::id());
+
+    let res = vault
+        .transfer_collateral(&mut test, sub_account, sub_account, pool, 1)
+        .await;
+    assert_custom_error(res, ErrorCode::InvalidCypherAccount);
+
+    let other_vault = VaultCookie::create_vault(&mut test, 1).await.unwrap();
+    let res = vault
+        .transfer_collateral(
+            &mut test,
+            vault.cypher_sub_account,
+            other_vault.cypher_sub_account,
+            pool,
+            1,
+        )
+        .await;
+    assert_custom_error(res, ErrorCode::InvalidCypherAccount);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transfer_collateral_respects_pause() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    let sub_account = vault
+        .create_strategy_sub_account(&mut test, 1, new_risk_params(120))
+        .await
+        .unwrap();
+    let pool = test.add_pool(&quote_mint::id());
+
+    vault.pause(&mut test, true).await.unwrap();
+
+    let res = vault
+        .transfer_collateral(&mut test, vault.cypher_sub_account, sub_account, pool, 1)
+        .await;
+    assert_custom_error(res, ErrorCode::VaultPaused);
+}
+
+#[test]
+fn risk_limits_require_equity_for_leverage() {
+    let vault: Box<Vault> = Box::new(bytemuck::Zeroable::zeroed());
+    let sub_account: Box<CypherSubAccount> = Box::new(bytemuck::Zeroable::zeroed());
+    let cache_account: Box<CacheAccount> = Box::new(bytemuck::Zeroable::zeroed());
+    let clearing = Clearing {
+        config: ClearingConfig {
+            maint_margin: 110,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // an empty sub account has no liabilities, so only the leverage limit can be breached
+    let mut risk_params = new_risk_params(120);
+    check_sub_account_risk_limits(
+        &vault,
+        &risk_params,
+        &sub_account,
+        &cache_account,
+        &clearing,
+    )
+    .unwrap();
+
+    risk_params.max_leverage = 300;
+    assert_eq!(
+        check_sub_account_risk_limits(
+            &vault,
+            &risk_params,
+            &sub_account,
+            &cache_account,
+            &clearing
+        )
+        .unwrap_err(),
+        Error::from(ErrorCode::LeverageExceeded)
+    );
+}
+
+#[test]
+fn only_allowlisted_markets_can_be_traded() {
+    let mut risk_params = new_risk_params(120);
+    let market = Pubkey::new_unique();
+    assert!(!risk_params.is_market_allowed(&market));
+
+    risk_params.allowed_markets[0] = market;
+    assert!(risk_params.is_market_allowed(&market));
+    assert!(!risk_params.is_market_allowed(&Pubkey::new_unique()));
+
+    // the unused entries of the allowlist never allow the default market
+    assert!(!risk_params.is_market_allowed(&Pubkey::default()));
+}
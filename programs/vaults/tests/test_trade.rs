@@ -0,0 +1,31 @@
+mod common;
+
+use crate::common::cookies::{assert_custom_error, init_new_test, VaultCookie};
+use vaults::{error::ErrorCode, MarketType};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn trades_are_signed_for_the_vault_accounts_only() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    let other_vault = VaultCookie::create_vault(&mut test, 1).await.unwrap();
+
+    let res = vault
+        .cancel_all_orders(
+            &mut test,
+            other_vault.cypher_account,
+            vault.cypher_sub_account,
+            MarketType::Perpetual,
+        )
+        .await;
+    assert_custom_error(res, ErrorCode::InvalidCypherAccount);
+
+    let res = vault
+        .cancel_all_orders(
+            &mut test,
+            vault.cypher_account,
+            other_vault.cypher_sub_account,
+            MarketType::Perpetual,
+        )
+        .await;
+    assert_custom_error(res, ErrorCode::InvalidCypherAccount);
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use crate::common::cookies::{assert_custom_error, init_new_test, VaultCookie};
+use anchor_lang::prelude::Pubkey;
+use cypher_client::quote_mint;
+use vaults::{error::ErrorCode, Vault, VaultType};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn single_token_vault_has_no_vault_lp_mint() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+
+    let res = vault.create_vault_lp_mint(&mut test, 6).await;
+    assert_custom_error(res, ErrorCode::InvalidVaultType);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vault_lp_mint_is_shared_by_every_token() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault_with_args(&mut test, 0, |args| {
+        args.vault_type = VaultType::MultiToken;
+    })
+    .await
+    .unwrap();
+    let token_mint = Pubkey::new_unique();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+    vault.open_deposits(&mut test, token_mint).await.unwrap();
+
+    // tokens that already issued their own LP tokens keep them
+    vault
+        .set_vault_state(&mut test, |vault| {
+            vault.token_infos_mut()[1].token_supply = 1
+        })
+        .await;
+    let res = vault.create_vault_lp_mint(&mut test, 6).await;
+    assert_custom_error(res, ErrorCode::TokenWithLpSupply);
+
+    vault
+        .set_vault_state(&mut test, |vault| {
+            vault.token_infos_mut()[1].token_supply = 0
+        })
+        .await;
+    test.advance_clock_by_slots(1).await;
+    vault.create_vault_lp_mint(&mut test, 6).await.unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert!(vault_state.has_vault_lp());
+    for token_mint in [quote_mint::id(), token_mint] {
+        assert_eq!(
+            vault_state.get_share_info(token_mint).unwrap().lp_mint,
+            vault.vault_lp_token_mint()
+        );
+    }
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use crate::common::{
+    cookies::{assert_custom_error, init_new_test, UserCookie, VaultCookie},
+    state::new_risk_params,
+};
+use anchor_lang::{error::ErrorCode as AnchorErrorCode, prelude::Pubkey};
+use cypher_client::quote_mint;
+use solana_sdk::signer::Signer;
+use vaults::{error::ErrorCode, CreateVaultArgs, Vault, WIND_DOWN_SETTLEMENT_DELAY};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn claim_requires_wind_down() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+
+    let user = UserCookie::new(&mut test).unwrap();
+    let lp_token_account = test
+        .create_token_account(
+            &user.keypair.pubkey(),
+            &vault.lp_token_mint(&quote_mint::id()),
+        )
+        .await;
+
+    let res = vault
+        .claim_wind_down(&mut test, &user, quote_mint::id(), lp_token_account, 1)
+        .await;
+    assert_custom_error(res, ErrorCode::VaultNotWindingDown);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn claim_ignores_pause_while_winding_down() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+
+    vault.wind_down(&mut test).await.unwrap();
+    vault.pause(&mut test, false).await.unwrap();
+
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert!(vault_state.is_winding_down());
+
+    // the claim gets past the pause and is only rejected for claiming more than the LP supply
+    let user = UserCookie::new(&mut test).unwrap();
+    let lp_token_account = test
+        .create_token_account(
+            &user.keypair.pubkey(),
+            &vault.lp_token_mint(&quote_mint::id()),
+        )
+        .await;
+
+    let res = vault
+        .claim_wind_down(&mut test, &user, quote_mint::id(), lp_token_account, 1)
+        .await;
+    assert_custom_error(res, ErrorCode::InvalidLpAmount);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn wind_down_can_not_be_repeated() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+
+    vault.wind_down(&mut test).await.unwrap();
+    test.advance_clock_by_slots(1).await;
+
+    let res = vault.wind_down(&mut test).await;
+    assert_custom_error(res, ErrorCode::VaultWindingDown);
+}
+
+#[test]
+fn wind_down_is_overdue_after_the_settlement_delay() {
+    let mut vault: Box<Vault> = Box::new(bytemuck::Zeroable::zeroed());
+    vault.init(Pubkey::new_unique(), 255, &CreateVaultArgs::default(), 100);
+    assert!(!vault.is_wind_down_overdue(i64::MAX));
+
+    vault.wind_down_at = 100;
+    assert!(!vault.is_wind_down_overdue(99 + WIND_DOWN_SETTLEMENT_DELAY));
+    assert!(vault.is_wind_down_overdue(100 + WIND_DOWN_SETTLEMENT_DELAY));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn anyone_can_only_move_collateral_home_once_wind_down_is_overdue() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    let sub_account = vault
+        .create_strategy_sub_account(&mut test, 1, new_risk_params(120))
+        .await
+        .unwrap();
+    let pool = test.add_pool(&quote_mint::id());
+    let user = UserCookie::new(&mut test).unwrap();
+
+    let res = vault
+        .transfer_collateral_as(
+            &mut test,
+            &user.keypair,
+            vault.cypher_sub_account,
+            sub_account,
+            pool,
+            1,
+        )
+        .await;
+    assert_custom_error(res, AnchorErrorCode::ConstraintHasOne);
+
+    vault.wind_down(&mut test).await.unwrap();
+    vault.pause(&mut test, false).await.unwrap();
+    // backdate the wind-down instead of warping the clock a whole week ahead
+    let now = test.get_clock().await.unix_timestamp;
+    vault
+        .set_vault_state(&mut test, |vault| {
+            vault.wind_down_at = now - WIND_DOWN_SETTLEMENT_DELAY
+        })
+        .await;
+
+    // the pause no longer holds anyone back, but the collateral can only go to the primary sub account
+    let res = vault
+        .transfer_collateral_as(
+            &mut test,
+            &user.keypair,
+            vault.cypher_sub_account,
+            sub_account,
+            pool,
+            1,
+        )
+        .await;
+    assert_custom_error(res, ErrorCode::InvalidCypherAccount);
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use crate::common::{
+    cookies::{assert_custom_error, init_new_test, UserCookie, VaultCookie},
+    state::new_vault,
+};
+use anchor_lang::{error::Error, prelude::Pubkey};
+use cypher_client::quote_mint;
+use solana_sdk::signer::Signer;
+use vaults::{error::ErrorCode, Vault, WithdrawTicket};
+
+#[test]
+fn withdraw_ticket_expires_after_max_withdraw_wait() {
+    let mut ticket = WithdrawTicket::default();
+    ticket.init(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        1_000,
+        0,
+        255,
+        100,
+    );
+
+    assert!(!ticket.is_expired(60, 159));
+    assert!(ticket.is_expired(60, 160));
+    assert!(ticket.is_expired(0, 100));
+    assert!(ticket.is_expired(i64::MAX, i64::MAX));
+}
+
+#[test]
+fn withdraw_reduces_principal_pro_rata() {
+    let (mut vault, token_mint) = new_vault();
+    vault
+        .record_deposit(token_mint, 1_000_000, 1_000_000, 1_000_000_000)
+        .unwrap();
+
+    // half of the LP tokens are redeemed for more than half of the principal after a gain
+    vault
+        .record_withdraw(token_mint, 600_000, 500_000_000)
+        .unwrap();
+    let token_info = vault.get_token_info(token_mint).unwrap();
+    assert_eq!(token_info.deposits, 500_000);
+    assert_eq!(token_info.token_supply, 500_000_000);
+
+    vault
+        .record_withdraw(token_mint, 600_000, 500_000_000)
+        .unwrap();
+    let token_info = vault.get_token_info(token_mint).unwrap();
+    assert_eq!(token_info.deposits, 0);
+    assert_eq!(token_info.token_supply, 0);
+    assert!(!vault.has_lp_supply());
+}
+
+#[test]
+fn withdraw_cannot_burn_more_than_supply() {
+    let (mut vault, token_mint) = new_vault();
+    vault
+        .record_deposit(token_mint, 1_000, 1_000, 1_000_000)
+        .unwrap();
+
+    assert!(vault.record_withdraw(token_mint, 1_000, 1_000_001).is_err());
+}
+
+#[test]
+fn unknown_token_mint_can_not_be_recorded() {
+    let (mut vault, _) = new_vault();
+    let token_mint = Pubkey::new_unique();
+
+    assert_eq!(
+        vault
+            .record_deposit(token_mint, 1_000, 1_000, 1_000_000)
+            .unwrap_err(),
+        Error::from(ErrorCode::InvalidTokenMint)
+    );
+    assert_eq!(
+        vault.record_withdraw(token_mint, 1_000, 1_000).unwrap_err(),
+        Error::from(ErrorCode::InvalidTokenMint)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn withdraw_ticket_is_settled_for_at_least_min_tokens_out() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+
+    let user = UserCookie::new(&mut test).unwrap();
+    let owner = user.keypair.pubkey();
+    let lp_token_account = Pubkey::new_unique();
+    test.add_token_account(
+        &lp_token_account,
+        &vault.lp_token_mint(&quote_mint::id()),
+        &owner,
+        1_000,
+    );
+
+    let res = vault
+        .request_withdraw(&mut test, &user, quote_mint::id(), lp_token_account, 0, 0)
+        .await;
+    assert_custom_error(res, ErrorCode::ZeroAmount);
+
+    vault
+        .request_withdraw(
+            &mut test,
+            &user,
+            quote_mint::id(),
+            lp_token_account,
+            1_000,
+            1_000,
+        )
+        .await
+        .unwrap();
+
+    let ticket = test
+        .load_anchor_account::<WithdrawTicket>(vault.withdraw_ticket(&quote_mint::id(), &owner))
+        .await;
+    assert_eq!(ticket.lp_amount, 1_000);
+    assert_eq!(ticket.min_tokens_out, 1_000);
+    let vault_state = test.get_zero_copy_account::<Vault>(vault.address).await;
+    assert_eq!(vault_state.withdraw_ticket_count, 1);
+
+    // the vault holds nothing, so the LP tokens are worth less than the user asked for
+    vault
+        .set_vault_state(&mut test, |v| v.token_infos_mut()[0].token_supply = 1_000)
+        .await;
+
+    let res = vault
+        .process_withdraw(&mut test, &user, quote_mint::id())
+        .await;
+    assert_custom_error(res, ErrorCode::SlippageExceeded);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn redeem_withdraws_at_least_min_tokens_out() {
+    let mut test = init_new_test().await.unwrap();
+    let vault = VaultCookie::create_vault(&mut test, 0).await.unwrap();
+    vault
+        .open_deposits(&mut test, quote_mint::id())
+        .await
+        .unwrap();
+    let user = UserCookie::new(&mut test).unwrap();
+
+    // an empty vault prices its virtual shares at a single token, so one LP token redeems for nothing
+    let res = vault.redeem(&mut test, &user, quote_mint::id(), 1, 0).await;
+    assert_custom_error(res, ErrorCode::ZeroAmount);
+
+    let res = vault
+        .redeem(&mut test, &user, quote_mint::id(), 2_000, 3)
+        .await;
+    assert_custom_error(res, ErrorCode::SlippageExceeded);
+}